@@ -0,0 +1,18 @@
+import { DurableObject, WorkerEntrypoint, WorkflowEntrypoint } from "cloudflare:workers";
+
+const INNER = Symbol("inner");
+
+// Exposes the methods of a wasm-bindgen class on the prototype of an
+// entrypoint or Durable Object, where the runtime looks for RPC methods and
+// handlers.
+function exposeMethods(Entrypoint, Inner) {
+  for (const name of Object.getOwnPropertyNames(Inner.prototype)) {
+    if (name === "constructor" || name === "free" || name.startsWith("__")) {
+      continue;
+    }
+
+    Entrypoint.prototype[name] = function (...args) {
+      return this[INNER][name](...args);
+    };
+  }
+}
@@ -4,4 +4,10 @@ export * from "./index_bg.js";
 // Run the worker's initialization function.
 imports.start?.();
 
-export default { fetch: imports.fetch, scheduled: imports.scheduled, queue: imports.queue };
\ No newline at end of file
+export default {
+  fetch: imports.fetch,
+  scheduled: imports.scheduled,
+  queue: imports.queue,
+  email: imports.email,
+  tail: imports.tail,
+};
@@ -14,6 +14,10 @@ const OUT_DIR: &str = "build";
 const OUT_NAME: &str = "index";
 const WORKER_SUBDIR: &str = "worker";
 
+const ENTRYPOINT_PREFIX: &str = "__betterworker_entrypoint_";
+const WORKFLOW_PREFIX: &str = "__betterworker_workflow_";
+const DURABLE_OBJECT_PREFIX: &str = "__betterworker_durable_object_";
+
 const WASM_IMPORT: &str = r#"let wasm;
 export function __wbg_set_wasm(val) {
     wasm = val;
@@ -41,7 +45,7 @@ pub fn main() -> Result<()> {
     use_glue_import()?;
 
     write_string_to_file(worker_path("glue.js"), include_str!("./js/glue.js"))?;
-    write_string_to_file(worker_path("shim.js"), include_str!("./js/shim.js"))?;
+    write_string_to_file(worker_path("shim.js"), generate_shim()?)?;
 
     bundle(&esbuild_path)?;
 
@@ -124,6 +128,84 @@ fn use_glue_import() -> Result<()> {
     Ok(())
 }
 
+// Generates the shim, adding a `WorkerEntrypoint` class for every class
+// exported with the `#[entrypoint]` macro, a `WorkflowEntrypoint` class for
+// every class exported with the `#[workflow]` macro and a `DurableObject` class
+// for every class exported with the `#[durable_object]` macro.
+fn generate_shim() -> Result<String> {
+    let mut shim = include_str!("./js/shim.js").to_string();
+
+    let bindgen_glue = read_file_to_string(worker_path(format!("{OUT_NAME}_bg.js")))?;
+    let entrypoints = exported_classes(&bindgen_glue, ENTRYPOINT_PREFIX);
+    let workflows = exported_classes(&bindgen_glue, WORKFLOW_PREFIX);
+    let durable_objects = exported_classes(&bindgen_glue, DURABLE_OBJECT_PREFIX);
+
+    if entrypoints.is_empty() && workflows.is_empty() && durable_objects.is_empty() {
+        return Ok(shim);
+    }
+
+    shim.push_str("\n\n");
+    shim.push_str(include_str!("./js/entrypoint.js"));
+    for name in entrypoints {
+        shim.push_str(&format!(
+            r#"
+export class {name} extends WorkerEntrypoint {{
+  constructor(ctx, env) {{
+    super(ctx, env);
+    this[INNER] = new imports.{ENTRYPOINT_PREFIX}{name}(ctx, env);
+  }}
+}}
+exposeMethods({name}, imports.{ENTRYPOINT_PREFIX}{name});
+"#
+        ));
+    }
+    for name in workflows {
+        shim.push_str(&format!(
+            r#"
+export class {name} extends WorkflowEntrypoint {{
+  constructor(ctx, env) {{
+    super(ctx, env);
+    this[INNER] = new imports.{WORKFLOW_PREFIX}{name}(ctx, env);
+  }}
+
+  run(event, step) {{
+    return this[INNER].run(event, step);
+  }}
+}}
+"#
+        ));
+    }
+    for name in durable_objects {
+        shim.push_str(&format!(
+            r#"
+export class {name} extends DurableObject {{
+  constructor(ctx, env) {{
+    super(ctx, env);
+    this[INNER] = new imports.{DURABLE_OBJECT_PREFIX}{name}(ctx, env);
+  }}
+}}
+exposeMethods({name}, imports.{DURABLE_OBJECT_PREFIX}{name});
+"#
+        ));
+    }
+
+    Ok(shim)
+}
+
+// Finds the names of the classes in the wasm-bindgen glue exported with the
+// given prefix.
+fn exported_classes(bindgen_glue: &str, prefix: &str) -> Vec<String> {
+    bindgen_glue
+        .lines()
+        .filter_map(|line| line.strip_prefix("export class ")?.strip_prefix(prefix))
+        .map(|rest| {
+            rest.chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .collect::<String>()
+        })
+        .collect()
+}
+
 // Bundles the snippets and worker-related code into a single file.
 fn bundle(esbuild_path: &Path) -> Result<()> {
     let no_minify = !matches!(env::var("NO_MINIFY"), Err(VarError::NotPresent));
@@ -133,6 +215,8 @@ fn bundle(esbuild_path: &Path) -> Result<()> {
     command.args([
         "--external:./index.wasm",
         "--external:cloudflare:sockets",
+        "--external:cloudflare:email",
+        "--external:cloudflare:workers",
         "--format=esm",
         "--bundle",
         "./shim.js",
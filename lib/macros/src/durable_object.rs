@@ -1,17 +1,23 @@
 use proc_macro2::{Ident, TokenStream};
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use syn::spanned::Spanned;
-use syn::{Error, FnArg, ImplItem, Item, Type, TypePath};
+use syn::{Error, FnArg, ImplItem, Item, ItemImpl, ItemStruct, LitStr, Meta, Type, Visibility};
+
+use crate::bindgen_module;
+use crate::entrypoint::{is_body, result_ok_type};
+
+/// The prefix of the JavaScript class exported for a Durable Object.
+/// `worker-build` looks for classes with this prefix and wraps them in a
+/// `DurableObject`, which the runtime requires for RPC.
+const DURABLE_OBJECT_PREFIX: &str = "__betterworker_durable_object_";
 
 pub fn expand_macro(tokens: TokenStream) -> syn::Result<TokenStream> {
     let item = syn::parse2::<Item>(tokens)?;
     match item {
+        Item::Impl(imp) if imp.trait_.is_none() => expand_rpc_methods(imp),
         Item::Impl(imp) => {
-            let impl_token = imp.impl_token;
             let trai = imp.trait_.clone();
-            let (_, trai, _) = trai.ok_or_else(|| {
-                Error::new_spanned(impl_token, "Must be a DurableObject trait impl")
-            })?;
+            let (_, trai, _) = trai.expect("inherent impls are handled above");
 
             if !trai
                 .segments
@@ -26,148 +32,107 @@ pub fn expand_macro(tokens: TokenStream) -> syn::Result<TokenStream> {
             }
 
             let pound = syn::Token![#](imp.span()).to_token_stream();
-            let wasm_bindgen_attr = quote! {
-                #pound[::betterworker::wasm_bindgen::prelude::wasm_bindgen]
-            };
-
-            let struct_name = imp.self_ty;
-            let items = imp.items;
+            let struct_name = struct_name(&imp.self_ty)?;
+            let js_class = format!("{DURABLE_OBJECT_PREFIX}{struct_name}");
+            let class_name = struct_name.to_string();
+            let wrapper_name = wrapper_name(struct_name);
+            let self_ty = &imp.self_ty;
             let mut tokenized = vec![];
-            let mut has_alarm = false;
-
-            for item in items {
-                let impl_method = match item {
-                    ImplItem::Fn(m) => m,
-                    _ => {
-                        return Err(Error::new_spanned(
-                            item,
-                            "Impl block must only contain methods",
-                        ))
-                    },
+
+            for item in &imp.items {
+                let ImplItem::Fn(impl_method) = item else {
+                    return Err(Error::new_spanned(
+                        item,
+                        "Impl block must only contain methods",
+                    ));
                 };
 
                 let tokens = match impl_method.sig.ident.to_string().as_str() {
-                    "new" => {
-                        let mut method = impl_method.clone();
-                        method.sig.ident = Ident::new("_new", method.sig.ident.span());
-
-                        // modify the `state` argument so it is type ObjectState
-                        let state_arg = method
-                            .sig
-                            .inputs
-                            .first_mut()
-                            .expect(
-                                "DurableObject `new` method must have 2 arguments: state and env",
-                            )
-                            .into_token_stream();
-                        let env_arg = method
-                            .sig
-                            .inputs
-                            .pop()
-                            .expect("DurableObject `new` method expects a second argument: env")
-                            .into_token_stream();
-
-                        let FnArg::Typed(mut state_pat) = syn::parse2::<FnArg>(state_arg)? else {
-                            return Err(Error::new(
-                                method.sig.inputs.span(),
-                                "DurableObject `new` method expects `state: State` as first \
-                                 argument.",
-                            ));
-                        };
-                        let FnArg::Typed(mut env_pat) = syn::parse2::<FnArg>(env_arg)? else {
-                            return Err(Error::new(
-                                method.sig.inputs.span(),
-                                "DurableObject `new` method expects `env: Env` as second argument.",
-                            ));
-                        };
-
-                        let path = syn::parse2::<TypePath>(quote! {
-                            betterworker::betterworker_sys::DurableObjectState
-                        })?;
-                        state_pat.ty = Box::new(Type::Path(path));
-
-                        let path = syn::parse2::<TypePath>(quote! {
-                            betterworker::betterworker_sys::Env
-                        })?;
-                        env_pat.ty = Box::new(Type::Path(path));
-
-                        let state_name = state_pat.pat.clone();
-                        let env_name = env_pat.pat.clone();
-
-                        method.sig.inputs.clear();
-                        method.sig.inputs.insert(0, FnArg::Typed(state_pat));
-                        method.sig.inputs.insert(1, FnArg::Typed(env_pat));
-
-                        // prepend the function block's statements to convert the ObjectState to
-                        // State type
-                        let mut prepended = Vec::with_capacity(8);
-                        if state_name.to_token_stream().to_string() != "_" {
-                            prepended.push(syn::parse_quote! {
-                                let state = ::betterworker::durable::State::from(#state_name);
-                            });
-                        };
-                        if env_name.to_token_stream().to_string() != "_" {
-                            prepended.push(syn::parse_quote! {
-                                let env = ::betterworker::env::Env::from(#env_name);
-                            });
-                        };
-                        prepended.extend(method.block.stmts);
-                        method.block.stmts = prepended;
-
-                        quote! {
-                            #pound[wasm_bindgen::prelude::wasm_bindgen(constructor)]
-                            pub #method
+                    "new" => continue,
+                    "fetch" => quote! {
+                        #pound[wasm_bindgen::prelude::wasm_bindgen(js_name = fetch)]
+                        pub fn _fetch(&self, req: ::betterworker::betterworker_sys::web_sys::Request) -> ::betterworker::js_sys::Promise {
+                            let inner = ::std::rc::Rc::clone(&self.0);
+
+                            ::betterworker::wasm_bindgen_futures::future_to_promise(async move {
+                                let result = ::betterworker::durable::DurableObject::fetch(&*inner, ::betterworker::http::request::from_web_sys_request(req)).await;
+                                let flushed = ::betterworker::durable::flush_persisted(&*inner).await;
+                                result.and_then(|value| flushed.map(|_| value))
+                                    .map(::betterworker::http::response::into_web_sys_response)
+                                    .map(::betterworker::wasm_bindgen::JsValue::from)
+                                    .map_err(::betterworker::wasm_bindgen::JsValue::from)
+                            })
                         }
                     },
-                    "fetch" => {
-                        let mut method = impl_method.clone();
-                        method.sig.ident = Ident::new("_fetch_raw", method.sig.ident.span());
-                        quote! {
-                            #pound[wasm_bindgen::prelude::wasm_bindgen(js_name = fetch)]
-                            pub fn _fetch(&mut self, req: ::betterworker::betterworker_sys::web_sys::Request) -> ::betterworker::js_sys::Promise {
-                                // SAFETY:
-                                // On the surface, this is unsound because the Durable Object could be dropped
-                                // while JavaScript still has possession of the future. However,
-                                // we know something that Rust doesn't: that the Durable Object will never be destroyed
-                                // while there is still a running promise inside of it, therefore we can let a reference
-                                // to the durable object escape into a static-lifetime future.
-                                let static_self: &'static mut Self = unsafe {&mut *(self as *mut _)};
-
-                                ::betterworker::wasm_bindgen_futures::future_to_promise(async move {
-                                    static_self._fetch_raw(::betterworker::http::request::from_web_sys_request(req)).await
-                                        .map(::betterworker::http::response::into_web_sys_response)
-                                        .map(::betterworker::wasm_bindgen::JsValue::from)
-                                        .map_err(::betterworker::wasm_bindgen::JsValue::from)
-                                })
-                            }
-                            #method
+                    "alarm" => quote! {
+                        #pound[wasm_bindgen::prelude::wasm_bindgen(js_name = alarm)]
+                        pub fn _alarm(&self, info: ::betterworker::wasm_bindgen::JsValue) -> ::betterworker::js_sys::Promise {
+                            let inner = ::std::rc::Rc::clone(&self.0);
+
+                            ::betterworker::wasm_bindgen_futures::future_to_promise(async move {
+                                let result = match ::betterworker::durable::AlarmInfo::from_js(info) {
+                                    Ok(info) => ::betterworker::durable::DurableObject::alarm(&*inner, info).await,
+                                    Err(err) => Err(err),
+                                };
+                                let flushed = ::betterworker::durable::flush_persisted(&*inner).await;
+                                result.and_then(|value| flushed.map(|_| value))
+                                    .map(::betterworker::http::response::into_web_sys_response)
+                                    .map(::betterworker::wasm_bindgen::JsValue::from)
+                                    .map_err(::betterworker::wasm_bindgen::JsValue::from)
+                            })
                         }
                     },
-                    "alarm" => {
-                        has_alarm = true;
-
-                        let mut method = impl_method.clone();
-                        method.sig.ident = Ident::new("_alarm_raw", method.sig.ident.span());
-                        quote! {
-                            #pound[wasm_bindgen::prelude::wasm_bindgen(js_name = alarm)]
-                            pub fn _alarm(&mut self) -> ::betterworker::js_sys::Promise {
-                                // SAFETY:
-                                // On the surface, this is unsound because the Durable Object could be dropped
-                                // while JavaScript still has possession of the future. However,
-                                // we know something that Rust doesn't: that the Durable Object will never be destroyed
-                                // while there is still a running promise inside of it, therefore we can let a reference
-                                // to the durable object escape into a static-lifetime future.
-                                let static_self: &'static mut Self = unsafe {&mut *(self as *mut _)};
-
-                                ::betterworker::wasm_bindgen_futures::future_to_promise(async move {
-                                    static_self._alarm_raw().await
-                                        .map(::betterworker::http::response::into_web_sys_response)
-                                        .map(::betterworker::wasm_bindgen::JsValue::from)
-                                        .map_err(::betterworker::wasm_bindgen::JsValue::from)
-                                })
-                            }
-
-                            #method
+                    "websocket_message" => quote! {
+                        #pound[wasm_bindgen::prelude::wasm_bindgen(js_name = webSocketMessage)]
+                        pub fn _websocket_message(
+                            &self, ws: ::betterworker::betterworker_sys::web_sys::WebSocket,
+                            message: ::betterworker::wasm_bindgen::JsValue,
+                        ) -> ::betterworker::js_sys::Promise {
+                            let inner = ::std::rc::Rc::clone(&self.0);
+                            let message = ::betterworker::websocket::WebSocketIncomingMessage::from_js(message);
+
+                            ::betterworker::wasm_bindgen_futures::future_to_promise(async move {
+                                let result = ::betterworker::durable::DurableObject::websocket_message(&*inner, ws.into(), message).await;
+                                let flushed = ::betterworker::durable::flush_persisted(&*inner).await;
+                                result.and_then(|value| flushed.map(|_| value))
+                                    .map(|_| ::betterworker::wasm_bindgen::JsValue::UNDEFINED)
+                                    .map_err(::betterworker::wasm_bindgen::JsValue::from)
+                            })
+                        }
+                    },
+                    "websocket_close" => quote! {
+                        #pound[wasm_bindgen::prelude::wasm_bindgen(js_name = webSocketClose)]
+                        pub fn _websocket_close(
+                            &self, ws: ::betterworker::betterworker_sys::web_sys::WebSocket,
+                            code: usize, reason: String, was_clean: bool,
+                        ) -> ::betterworker::js_sys::Promise {
+                            let inner = ::std::rc::Rc::clone(&self.0);
+
+                            ::betterworker::wasm_bindgen_futures::future_to_promise(async move {
+                                let result = ::betterworker::durable::DurableObject::websocket_close(&*inner, ws.into(), code, reason, was_clean).await;
+                                let flushed = ::betterworker::durable::flush_persisted(&*inner).await;
+                                result.and_then(|value| flushed.map(|_| value))
+                                    .map(|_| ::betterworker::wasm_bindgen::JsValue::UNDEFINED)
+                                    .map_err(::betterworker::wasm_bindgen::JsValue::from)
+                            })
+                        }
+                    },
+                    "websocket_error" => quote! {
+                        #pound[wasm_bindgen::prelude::wasm_bindgen(js_name = webSocketError)]
+                        pub fn _websocket_error(
+                            &self, ws: ::betterworker::betterworker_sys::web_sys::WebSocket,
+                            error: ::betterworker::wasm_bindgen::JsValue,
+                        ) -> ::betterworker::js_sys::Promise {
+                            let inner = ::std::rc::Rc::clone(&self.0);
+                            let error = ::betterworker::error::WorkerError::__from_js_err(error);
+
+                            ::betterworker::wasm_bindgen_futures::future_to_promise(async move {
+                                let result = ::betterworker::durable::DurableObject::websocket_error(&*inner, ws.into(), error).await;
+                                let flushed = ::betterworker::durable::flush_persisted(&*inner).await;
+                                result.and_then(|value| flushed.map(|_| value))
+                                    .map(|_| ::betterworker::wasm_bindgen::JsValue::UNDEFINED)
+                                    .map_err(::betterworker::wasm_bindgen::JsValue::from)
+                            })
                         }
                     },
                     _ => panic!(),
@@ -175,52 +140,309 @@ pub fn expand_macro(tokens: TokenStream) -> syn::Result<TokenStream> {
                 tokenized.push(tokens);
             }
 
-            let alarm_tokens = has_alarm.then(|| quote! {
-                async fn alarm(
-                    &mut self,
-                ) -> ::betterworker::result::Result<::betterworker::http::Response<::betterworker::body::Body>> {
-                    self._alarm_raw().await
-                }
-            });
-            Ok(quote! {
-                #wasm_bindgen_attr
-                impl #struct_name {
+            let handlers = bindgen_module(format_ident!("{wrapper_name}_handlers"), quote! {
+                #pound[::betterworker::wasm_bindgen::prelude::wasm_bindgen(js_class = #js_class)]
+                impl #wrapper_name {
+                    #pound[wasm_bindgen::prelude::wasm_bindgen(constructor)]
+                    pub fn new(
+                        state: ::betterworker::betterworker_sys::DurableObjectState,
+                        env: ::betterworker::betterworker_sys::Env,
+                    ) -> Self {
+                        let state = ::betterworker::durable::State::from(state)
+                            .__with_class_name(#class_name);
+                        let storage = state.storage();
+                        let inner = <#self_ty as ::betterworker::durable::DurableObject>::new(
+                            state,
+                            ::betterworker::env::Env::from(env),
+                        );
+                        ::betterworker::durable::PersistedFields::bind(&inner, &storage);
+                        Self(::std::rc::Rc::new(inner))
+                    }
+
                     #(#tokenized)*
                 }
+            });
 
-                impl ::betterworker::durable::DurableObject for #struct_name {
-                    fn new(state: ::betterworker::durable::State, env: ::betterworker::env::Env) -> Self {
-                        Self::_new(state._inner(), env._inner())
-                    }
-
-                    async fn fetch(
-                        &mut self, req: ::betterworker::http::Request<::betterworker::body::Body>,
-                    ) -> ::betterworker::result::Result<::betterworker::http::Response<::betterworker::body::Body>> {
-                        self._fetch_raw(req).await
-                    }
+            Ok(quote! {
+                #imp
 
-                    #alarm_tokens
-                }
+                #handlers
 
                 trait __Need_Durable_Object_Trait_Impl_With_durable_object_Attribute { const MACROED: bool = true; }
-                impl __Need_Durable_Object_Trait_Impl_With_durable_object_Attribute for #struct_name {}
+                impl __Need_Durable_Object_Trait_Impl_With_durable_object_Attribute for #self_ty {}
             })
         },
-        Item::Struct(struc) => {
+        Item::Struct(mut struc) => {
+            let persisted = take_persisted_fields(&mut struc)?;
             let tokens = struc.to_token_stream();
             let pound = syn::Token![#](struc.span()).to_token_stream();
-            let struct_name = struc.ident;
+            let struct_name = &struc.ident;
+            let js_class = format!("{DURABLE_OBJECT_PREFIX}{struct_name}");
+            let wrapper_name = wrapper_name(struct_name);
+            let fields = persisted.iter().map(|(field, _)| field);
+            let keys = persisted.iter().map(|(_, key)| key);
+            let staged = persisted.iter().map(|(field, _)| field);
+            let export = format_ident!("{wrapper_name}_export");
+            let wrapper = bindgen_module(export.clone(), quote! {
+                #[doc(hidden)]
+                #pound[::betterworker::wasm_bindgen::prelude::wasm_bindgen(js_name = #js_class)]
+                pub struct #wrapper_name(pub(super) ::std::rc::Rc<#struct_name>);
+            });
             Ok(quote! {
-                #pound[::betterworker::wasm_bindgen::prelude::wasm_bindgen]
                 #tokens
 
+                impl ::betterworker::durable::PersistedFields for #struct_name {
+                    fn bind(&self, storage: &::betterworker::durable::Storage) {
+                        #(self.#fields.bind(#keys, storage);)*
+                    }
+
+                    fn stage<'a>(
+                        &'a self, batch: &mut ::betterworker::durable::PersistBatch<'a>,
+                    ) -> ::betterworker::result::Result<()> {
+                        #(self.#staged.stage(batch)?;)*
+                        Ok(())
+                    }
+                }
+
+                #wrapper
+                use #export::#wrapper_name;
+
                 const _: bool = <#struct_name as __Need_Durable_Object_Trait_Impl_With_durable_object_Attribute>::MACROED;
             })
         },
         _ => Err(Error::new(
             item.span(),
-            "Durable Object macro can only be applied to structs and their impl of DurableObject \
-             trait",
+            "Durable Object macro can only be applied to structs, their impl of DurableObject \
+             trait and an impl block of RPC methods",
         )),
     }
 }
+
+/// Exports every `pub async fn` of an inherent impl block as a JavaScript RPC
+/// method, and generates a typed client wrapping a `Stub` with the same
+/// methods.
+fn expand_rpc_methods(imp: ItemImpl) -> syn::Result<TokenStream> {
+    let struct_name = struct_name(&imp.self_ty)?;
+    let wrapper_name = wrapper_name(struct_name);
+    let pound = syn::Token![#](imp.span()).to_token_stream();
+    let js_class = format!("{DURABLE_OBJECT_PREFIX}{struct_name}");
+    let client_name = format_ident!("{}Stub", struct_name);
+
+    let mut tokenized = vec![];
+    let mut client_methods = vec![];
+
+    for item in &imp.items {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        let name = &method.sig.ident;
+
+        let receiver = match method.sig.inputs.first() {
+            Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() => receiver,
+            _ => continue,
+        };
+        if !matches!(method.vis, Visibility::Public(_)) {
+            continue;
+        }
+        if receiver.mutability.is_some() {
+            return Err(Error::new_spanned(
+                receiver,
+                "Durable Object RPC methods must take `&self`, keep mutable state in a `Cell` or \
+                 `RefCell`",
+            ));
+        }
+
+        if matches!(
+            name.to_string().as_str(),
+            "new" | "fetch" | "alarm" | "websocket_message" | "websocket_close" | "websocket_error"
+        ) {
+            return Err(Error::new_spanned(
+                name,
+                "RPC methods cannot share the name of a DurableObject handler",
+            ));
+        }
+        if method.sig.asyncness.is_none() {
+            return Err(Error::new_spanned(
+                &method.sig,
+                "Durable Object RPC methods must be async",
+            ));
+        }
+
+        let mut params = vec![];
+        let mut conversions = vec![];
+        let mut client_params = vec![];
+        let mut client_args = vec![];
+        let mut args = vec![];
+        for (i, input) in method.sig.inputs.iter().skip(1).enumerate() {
+            let FnArg::Typed(input) = input else {
+                unreachable!("only the first argument can be a receiver");
+            };
+            let param = format_ident!("arg{}", i);
+            let ty = &input.ty;
+            params.push(quote! { #param: ::betterworker::wasm_bindgen::JsValue });
+            client_params.push(quote! { #param: #ty });
+            if is_body(ty) {
+                conversions
+                    .push(quote! { let #param = ::betterworker::rpc::body_from_js(#param)?; });
+                client_args.push(quote! { .body(#param) });
+            } else {
+                conversions
+                    .push(quote! { let #param = ::betterworker::rpc::from_js::<#ty>(#param)?; });
+                client_args.push(quote! { .arg(&#param) });
+            }
+            args.push(param);
+        }
+
+        let ok_ty = result_ok_type(&method.sig.output).ok_or_else(|| {
+            Error::new(
+                method.sig.output.span(),
+                "Durable Object RPC methods must return `Result<T>`",
+            )
+        })?;
+        let (into_js, client_call) = if is_body(ok_ty) {
+            (
+                quote! { Ok(::betterworker::rpc::body_to_js(value)) },
+                quote! { call_body() },
+            )
+        } else {
+            (
+                quote! { ::betterworker::rpc::to_js(&value) },
+                quote! { call() },
+            )
+        };
+
+        let js_name = name.to_string();
+        let wrapper_method = Ident::new(&format!("_{name}"), name.span());
+        tokenized.push(quote! {
+            #pound[wasm_bindgen::prelude::wasm_bindgen(js_name = #js_name)]
+            pub fn #wrapper_method(&self, #(#params),*) -> ::betterworker::js_sys::Promise {
+                let inner = ::std::rc::Rc::clone(&self.0);
+
+                ::betterworker::wasm_bindgen_futures::future_to_promise(async move {
+                    let result: ::betterworker::result::Result<::betterworker::wasm_bindgen::JsValue> = async {
+                        #(#conversions)*
+                        let value = inner.#name(#(#args),*).await;
+                        let flushed = ::betterworker::durable::flush_persisted(&*inner).await;
+                        let value = value.and_then(|value| flushed.map(|_| value))?;
+                        #into_js
+                    }.await;
+                    result.map_err(::betterworker::wasm_bindgen::JsValue::from)
+                })
+            }
+        });
+
+        let docs = method
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"));
+        client_methods.push(quote! {
+            #(#docs)*
+            pub async fn #name(
+                &self, #(#client_params),*
+            ) -> ::betterworker::result::Result<#ok_ty> {
+                self.0.rpc(#js_name)#(#client_args)*.#client_call.await
+            }
+        });
+    }
+
+    let client_doc =
+        format!("A typed client for the RPC methods of the [`{struct_name}`] Durable Object.");
+
+    let rpc = bindgen_module(format_ident!("{wrapper_name}_rpc"), quote! {
+        #pound[::betterworker::wasm_bindgen::prelude::wasm_bindgen(js_class = #js_class)]
+        impl #wrapper_name {
+            #(#tokenized)*
+        }
+    });
+
+    Ok(quote! {
+        #imp
+
+        #rpc
+
+        #[doc = #client_doc]
+        pub struct #client_name(::betterworker::durable::Stub);
+
+        impl #client_name {
+            #(#client_methods)*
+
+            /// The stub the client sends its calls to.
+            pub fn stub(&self) -> &::betterworker::durable::Stub {
+                &self.0
+            }
+        }
+
+        impl ::std::convert::From<::betterworker::durable::Stub> for #client_name {
+            fn from(stub: ::betterworker::durable::Stub) -> Self {
+                Self(stub)
+            }
+        }
+    })
+}
+
+/// Removes the `#[persist]` attributes from the fields of `struc`, and returns
+/// the fields they were on with the storage key of each.
+fn take_persisted_fields(struc: &mut ItemStruct) -> syn::Result<Vec<(Ident, LitStr)>> {
+    let mut persisted = vec![];
+    for field in struc.fields.iter_mut() {
+        let mut key = None;
+        let mut error = None;
+        field.attrs.retain(|attr| {
+            if !attr.path().is_ident("persist") {
+                return true;
+            }
+
+            let Some(ident) = &field.ident else {
+                error = Some(Error::new_spanned(
+                    attr,
+                    "`#[persist]` can only be used on named fields",
+                ));
+                return false;
+            };
+            let mut name = LitStr::new(&ident.to_string(), ident.span());
+            if let Meta::List(_) = attr.meta {
+                let parsed = attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("key") {
+                        name = meta.value()?.parse()?;
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `key = \"...\"`"))
+                    }
+                });
+                if let Err(err) = parsed {
+                    error = Some(err);
+                }
+            }
+            key = Some((ident.clone(), name));
+            false
+        });
+
+        if let Some(err) = error {
+            return Err(err);
+        }
+        persisted.extend(key);
+    }
+
+    Ok(persisted)
+}
+
+/// The name of the exported type holding the Durable Object `struct_name`.
+/// The object is kept in an `Rc`, so each event gets its own handle to it and
+/// several events can run concurrently.
+fn wrapper_name(struct_name: &Ident) -> Ident {
+    format_ident!("__{}DurableObject", struct_name)
+}
+
+fn struct_name(ty: &Type) -> syn::Result<&Ident> {
+    let Type::Path(path) = ty else {
+        return Err(Error::new_spanned(
+            ty,
+            "Durable Object must be a named type",
+        ));
+    };
+    path.path
+        .segments
+        .last()
+        .map(|segment| &segment.ident)
+        .ok_or_else(|| Error::new_spanned(ty, "Durable Object must be a named type"))
+}
@@ -0,0 +1,190 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::{
+    Error, FnArg, GenericArgument, ImplItem, ItemImpl, PathArguments, ReturnType, Type, Visibility,
+};
+
+use crate::bindgen_module;
+
+/// The prefix of the JavaScript class exported for an entrypoint.
+/// `worker-build` looks for classes with this prefix and wraps them in a
+/// `WorkerEntrypoint`.
+const ENTRYPOINT_PREFIX: &str = "__betterworker_entrypoint_";
+
+pub fn expand_macro(tokens: TokenStream) -> syn::Result<TokenStream> {
+    let imp = syn::parse2::<ItemImpl>(tokens)?;
+    if let Some((_, trai, _)) = &imp.trait_ {
+        return Err(Error::new_spanned(
+            trai,
+            "Entrypoint macro must be applied to an inherent impl block",
+        ));
+    }
+
+    let Type::Path(self_ty) = imp.self_ty.as_ref() else {
+        return Err(Error::new_spanned(
+            &imp.self_ty,
+            "Entrypoint must be a named type",
+        ));
+    };
+    let struct_name = &self_ty
+        .path
+        .segments
+        .last()
+        .ok_or_else(|| Error::new_spanned(self_ty, "Entrypoint must be a named type"))?
+        .ident;
+    let self_ty = &imp.self_ty;
+
+    let pound = syn::Token![#](imp.span()).to_token_stream();
+    let js_class = format!("{ENTRYPOINT_PREFIX}{struct_name}");
+    let wrapper_name = format_ident!("__{}Entrypoint", struct_name);
+
+    let mut has_new = false;
+    let mut tokenized = vec![];
+
+    for item in &imp.items {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        let name = &method.sig.ident;
+
+        if name == "new" {
+            has_new = true;
+            continue;
+        }
+
+        let takes_ref_self = match method.sig.inputs.first() {
+            Some(FnArg::Receiver(receiver)) => {
+                receiver.reference.is_some() && receiver.mutability.is_none()
+            },
+            _ => false,
+        };
+        if !matches!(method.vis, Visibility::Public(_)) || !takes_ref_self {
+            continue;
+        }
+
+        if method.sig.asyncness.is_none() {
+            return Err(Error::new_spanned(
+                &method.sig,
+                "Entrypoint methods taking `&self` must be async",
+            ));
+        }
+
+        let mut params = vec![];
+        let mut conversions = vec![];
+        let mut args = vec![];
+        for (i, input) in method.sig.inputs.iter().skip(1).enumerate() {
+            let FnArg::Typed(input) = input else {
+                unreachable!("only the first argument can be a receiver");
+            };
+            let param = format_ident!("arg{}", i);
+            let ty = &input.ty;
+            params.push(quote! { #param: ::betterworker::wasm_bindgen::JsValue });
+            conversions.push(if is_body(ty) {
+                quote! { let #param = ::betterworker::rpc::body_from_js(#param)?; }
+            } else {
+                quote! { let #param = ::betterworker::rpc::from_js::<#ty>(#param)?; }
+            });
+            args.push(param);
+        }
+
+        let ok_ty = result_ok_type(&method.sig.output).ok_or_else(|| {
+            Error::new(
+                method.sig.output.span(),
+                "Entrypoint methods must return `Result<T>`",
+            )
+        })?;
+        let into_js = if is_body(ok_ty) {
+            quote! { Ok(::betterworker::rpc::body_to_js(value)) }
+        } else {
+            quote! { ::betterworker::rpc::to_js(&value) }
+        };
+
+        let js_name = name.to_string();
+        let wrapper_method = Ident::new(&format!("_{name}"), name.span());
+        tokenized.push(quote! {
+            #pound[wasm_bindgen::prelude::wasm_bindgen(js_name = #js_name)]
+            pub fn #wrapper_method(&self, #(#params),*) -> ::betterworker::js_sys::Promise {
+                let inner = ::std::rc::Rc::clone(&self.0);
+
+                ::betterworker::wasm_bindgen_futures::future_to_promise(async move {
+                    let result: ::betterworker::result::Result<::betterworker::wasm_bindgen::JsValue> = async {
+                        #(#conversions)*
+                        let value = inner.#name(#(#args),*).await?;
+                        #into_js
+                    }.await;
+                    result.map_err(::betterworker::wasm_bindgen::JsValue::from)
+                })
+            }
+        });
+    }
+
+    if !has_new {
+        return Err(Error::new(
+            imp.span(),
+            "Entrypoint must have a `new(ctx: Context, env: Env) -> Self` method",
+        ));
+    }
+
+    let exported = bindgen_module(format_ident!("{wrapper_name}_export"), quote! {
+        #[doc(hidden)]
+        #pound[::betterworker::wasm_bindgen::prelude::wasm_bindgen(js_name = #js_class)]
+        pub struct #wrapper_name(::std::rc::Rc<#self_ty>);
+
+        #pound[::betterworker::wasm_bindgen::prelude::wasm_bindgen(js_class = #js_class)]
+        impl #wrapper_name {
+            #pound[wasm_bindgen::prelude::wasm_bindgen(constructor)]
+            pub fn new(
+                ctx: ::betterworker::betterworker_sys::Context, env: ::betterworker::betterworker_sys::Env,
+            ) -> Self {
+                Self(::std::rc::Rc::new(<#self_ty>::new(
+                    ::betterworker::context::Context::new(ctx),
+                    ::betterworker::env::Env::from(env),
+                )))
+            }
+
+            #(#tokenized)*
+        }
+    });
+
+    Ok(quote! {
+        #imp
+
+        #exported
+    })
+}
+
+/// Whether `ty` names the `Body` type, which is passed as a `ReadableStream`
+/// rather than converted with `serde`.
+pub(crate) fn is_body(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident == "Body")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Extracts `T` from a `Result<T>` or `Result<T, E>` return type.
+pub(crate) fn result_ok_type(output: &ReturnType) -> Option<&Type> {
+    let ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+    let Type::Path(path) = ty.as_ref() else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
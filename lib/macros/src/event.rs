@@ -12,6 +12,8 @@ pub fn expand_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
         Fetch,
         Scheduled,
         Start,
+        Email,
+        Tail,
         #[cfg(feature = "queue")]
         Queue,
     }
@@ -25,6 +27,8 @@ pub fn expand_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
             "fetch" => handler_type = Some(Fetch),
             "scheduled" => handler_type = Some(Scheduled),
             "start" => handler_type = Some(Start),
+            "email" => handler_type = Some(Email),
+            "tail" => handler_type = Some(Tail),
             #[cfg(feature = "queue")]
             "queue" => handler_type = Some(Queue),
             "respond_with_errors" => {
@@ -34,7 +38,8 @@ pub fn expand_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     }
     let handler_type = handler_type.expect(
-        "must have either 'fetch', 'scheduled', 'queue' or 'start' attribute, e.g. #[event(fetch)]",
+        "must have either 'fetch', 'scheduled', 'queue', 'email', 'tail' or 'start' attribute, \
+         e.g. #[event(fetch)]",
     );
 
     // create new var using syn item of the attributed fn
@@ -172,6 +177,85 @@ pub fn expand_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
 
             TokenStream::from(output)
         },
+        Email => {
+            // save original fn name for re-use in the wrapper fn
+            let input_fn_ident = Ident::new(
+                &(input_fn.sig.ident.to_string() + "_email_glue"),
+                input_fn.sig.ident.span(),
+            );
+            let wrapper_fn_ident = Ident::new("email", input_fn.sig.ident.span());
+            // rename the original attributed fn
+            input_fn.sig.ident = input_fn_ident.clone();
+
+            let wrapper_fn = quote! {
+                pub async fn #wrapper_fn_ident(message: ::betterworker::betterworker_sys::ForwardableEmailMessage, env: ::betterworker::betterworker_sys::Env, ctx: ::betterworker::betterworker_sys::Context) {
+                    // call the original fn
+                    let ctx = ::betterworker::context::Context::new(ctx);
+                    match #input_fn_ident(::betterworker::email::ForwardableEmailMessage::new(message), ::betterworker::env::Env::from(env), ctx).await {
+                        Ok(()) => {},
+                        Err(e) => {
+                            ::betterworker::betterworker_sys::console_error!("{}", &e);
+                            panic!("{}", e);
+                        }
+                    }
+                }
+            };
+            let wasm_bindgen_code =
+                wasm_bindgen_macro_support::expand(TokenStream::new().into(), wrapper_fn)
+                    .expect("wasm_bindgen macro failed to expand");
+
+            let output = quote! {
+                #input_fn
+
+                mod _worker_email {
+                    use ::betterworker::{wasm_bindgen, wasm_bindgen_futures};
+                    use super::#input_fn_ident;
+                    #wasm_bindgen_code
+                }
+            };
+
+            TokenStream::from(output)
+        },
+        Tail => {
+            // save original fn name for re-use in the wrapper fn
+            let input_fn_ident = Ident::new(
+                &(input_fn.sig.ident.to_string() + "_tail_glue"),
+                input_fn.sig.ident.span(),
+            );
+            let wrapper_fn_ident = Ident::new("tail", input_fn.sig.ident.span());
+            // rename the original attributed fn
+            input_fn.sig.ident = input_fn_ident.clone();
+
+            let wrapper_fn = quote! {
+                pub async fn #wrapper_fn_ident(events: ::betterworker::js_sys::Array, env: ::betterworker::betterworker_sys::Env, ctx: ::betterworker::betterworker_sys::Context) {
+                    // call the original fn
+                    let ctx = ::betterworker::context::Context::new(ctx);
+                    let result = match ::betterworker::tail::from_js(events) {
+                        Ok(events) => #input_fn_ident(events, ::betterworker::env::Env::from(env), ctx).await,
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = result {
+                        ::betterworker::betterworker_sys::console_error!("{}", &e);
+                        panic!("{}", e);
+                    }
+                }
+            };
+            let wasm_bindgen_code =
+                wasm_bindgen_macro_support::expand(TokenStream::new().into(), wrapper_fn)
+                    .expect("wasm_bindgen macro failed to expand");
+
+            let output = quote! {
+                #input_fn
+
+                mod _worker_tail {
+                    use ::betterworker::{wasm_bindgen, wasm_bindgen_futures};
+                    use super::#input_fn_ident;
+                    #wasm_bindgen_code
+                }
+            };
+
+            TokenStream::from(output)
+        },
         Start => {
             // save original fn name for re-use in the wrapper fn
             let input_fn_ident = Ident::new(
@@ -1,7 +1,10 @@
 mod durable_object;
+mod entrypoint;
 mod event;
+mod workflow;
 
 use proc_macro::TokenStream;
+use quote::quote;
 
 #[proc_macro_attribute]
 pub fn durable_object(_attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -10,7 +13,40 @@ pub fn durable_object(_attr: TokenStream, item: TokenStream) -> TokenStream {
         .into()
 }
 
+#[proc_macro_attribute]
+pub fn entrypoint(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    entrypoint::expand_macro(item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 #[proc_macro_attribute]
 pub fn event(attr: TokenStream, item: TokenStream) -> TokenStream {
     event::expand_macro(attr, item)
 }
+
+#[proc_macro_attribute]
+pub fn workflow(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    workflow::expand_macro(item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Puts `items` in a hidden module that sees the items around it. The code
+/// `#[wasm_bindgen]` generates for structs and impl blocks refers to a
+/// `wasm_bindgen` path in scope, which the module imports from `betterworker`
+/// so that callers don't have to.
+fn bindgen_module(
+    name: proc_macro2::Ident, items: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    quote! {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        mod #name {
+            use super::*;
+            use ::betterworker::wasm_bindgen;
+
+            #items
+        }
+    }
+}
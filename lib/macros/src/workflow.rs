@@ -0,0 +1,100 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::{Error, ImplItem, ItemImpl, Type};
+
+use crate::bindgen_module;
+
+/// The prefix of the JavaScript class exported for a workflow. `worker-build`
+/// looks for classes with this prefix and wraps them in a
+/// `WorkflowEntrypoint`.
+const WORKFLOW_PREFIX: &str = "__betterworker_workflow_";
+
+pub fn expand_macro(tokens: TokenStream) -> syn::Result<TokenStream> {
+    let imp = syn::parse2::<ItemImpl>(tokens)?;
+    if let Some((_, trai, _)) = &imp.trait_ {
+        return Err(Error::new_spanned(
+            trai,
+            "Workflow macro must be applied to an inherent impl block",
+        ));
+    }
+
+    let Type::Path(self_ty) = imp.self_ty.as_ref() else {
+        return Err(Error::new_spanned(
+            &imp.self_ty,
+            "Workflow must be a named type",
+        ));
+    };
+    let struct_name = &self_ty
+        .path
+        .segments
+        .last()
+        .ok_or_else(|| Error::new_spanned(self_ty, "Workflow must be a named type"))?
+        .ident;
+    let self_ty = &imp.self_ty;
+
+    let has_method = |name: &str| {
+        imp.items
+            .iter()
+            .any(|item| matches!(item, ImplItem::Fn(method) if method.sig.ident == name))
+    };
+    if !has_method("new") {
+        return Err(Error::new(
+            imp.span(),
+            "Workflow must have a `new(ctx: Context, env: Env) -> Self` method",
+        ));
+    }
+    if !has_method("run") {
+        return Err(Error::new(
+            imp.span(),
+            "Workflow must have an `async fn run(&self, event: WorkflowEvent<T>, step: \
+             WorkflowStep) -> Result<U>` method",
+        ));
+    }
+
+    let pound = syn::Token![#](imp.span()).to_token_stream();
+    let js_class = format!("{WORKFLOW_PREFIX}{struct_name}");
+    let wrapper_name = format_ident!("__{}Workflow", struct_name);
+
+    let exported = bindgen_module(format_ident!("{wrapper_name}_export"), quote! {
+        #[doc(hidden)]
+        #pound[::betterworker::wasm_bindgen::prelude::wasm_bindgen(js_name = #js_class)]
+        pub struct #wrapper_name(::std::rc::Rc<#self_ty>);
+
+        #pound[::betterworker::wasm_bindgen::prelude::wasm_bindgen(js_class = #js_class)]
+        impl #wrapper_name {
+            #pound[wasm_bindgen::prelude::wasm_bindgen(constructor)]
+            pub fn new(
+                ctx: ::betterworker::betterworker_sys::Context, env: ::betterworker::betterworker_sys::Env,
+            ) -> Self {
+                Self(::std::rc::Rc::new(<#self_ty>::new(
+                    ::betterworker::context::Context::new(ctx),
+                    ::betterworker::env::Env::from(env),
+                )))
+            }
+
+            #pound[wasm_bindgen::prelude::wasm_bindgen(js_name = run)]
+            pub fn _run(
+                &self, event: ::betterworker::wasm_bindgen::JsValue, step: ::betterworker::betterworker_sys::WorkflowStep,
+            ) -> ::betterworker::js_sys::Promise {
+                let inner = ::std::rc::Rc::clone(&self.0);
+
+                ::betterworker::wasm_bindgen_futures::future_to_promise(async move {
+                    let result: ::betterworker::result::Result<::betterworker::wasm_bindgen::JsValue> = async {
+                        let event = ::betterworker::workflow::WorkflowEvent::from_js(event)?;
+                        let step = ::betterworker::workflow::WorkflowStep::from(step);
+                        let output = inner.run(event, step).await?;
+                        ::betterworker::rpc::to_js(&output)
+                    }.await;
+                    result.map_err(::betterworker::wasm_bindgen::JsValue::from)
+                })
+            }
+        }
+    });
+
+    Ok(quote! {
+        #imp
+
+        #exported
+    })
+}
@@ -1,7 +1,16 @@
 use std::time::Duration;
 
 use betterworker::prelude::*;
-use betterworker::wasm_bindgen;
+use serde::Serialize;
+
+/// The timers that fired on an [`AlarmObject`], and the retry the alarm that
+/// fired them was on.
+#[derive(Serialize)]
+struct FiredTimers {
+    fired: Vec<String>,
+    retry_count: Option<u32>,
+    is_retry: Option<bool>,
+}
 
 #[durable_object]
 pub struct AlarmObject {
@@ -14,7 +23,11 @@ impl DurableObject for AlarmObject {
         Self { state }
     }
 
-    async fn fetch(&mut self, _: Request<Body>) -> Result<Response<Body>, WorkerError> {
+    async fn fetch(&self, req: Request<Body>) -> Result<Response<Body>, WorkerError> {
+        if req.uri().path() == "/timers" {
+            return self.timers().await;
+        }
+
         let alarmed: bool = match self.state.storage().get("alarmed").await {
             Ok(alarmed) => alarmed,
             Err(e) if e.to_string() == "No such value in storage." => {
@@ -31,10 +44,67 @@ impl DurableObject for AlarmObject {
         Ok(Response::new(alarmed.to_string().into()))
     }
 
-    async fn alarm(&mut self) -> Result<Response<Body>, WorkerError> {
-        self.state.storage().put("alarmed", true).await?;
+    async fn alarm(&self, info: AlarmInfo) -> Result<Response<Body>, WorkerError> {
+        let mut storage = self.state.storage();
+
+        // The first attempt of the alarm of the timers fails, so that the
+        // runtime retries it.
+        if storage.get_opt::<bool>("fail_alarm").await?.is_some() {
+            if !info.is_retry {
+                return Err(WorkerError::JsError("Failing the first attempt".into()));
+            }
+            storage.delete("fail_alarm").await?;
+            storage.put("timers_retry_count", info.retry_count).await?;
+            storage.put("timers_is_retry", info.is_retry).await?;
+        }
+
+        let fired = Timers::new(storage.clone()).take_due().await?;
+        if !fired.is_empty() {
+            let mut all = storage
+                .get_opt::<Vec<String>>("fired")
+                .await?
+                .unwrap_or_default();
+            all.extend(fired);
+            storage.put("fired", all).await?;
+            return Ok(Response::new("TIMERS".into()));
+        }
+
+        storage.put("alarmed", true).await?;
+        storage.put("retry_count", info.retry_count).await?;
 
         console_log!("Alarm has been triggered!");
         Ok(Response::new("ALARMED".into()))
     }
 }
+
+impl AlarmObject {
+    /// Sets two timers on the first request, and returns the timers that fired
+    /// on the following ones.
+    async fn timers(&self) -> Result<Response<Body>, WorkerError> {
+        let storage = self.state.storage();
+        let mut timers = Timers::new(storage.clone());
+
+        if timers.list().await?.is_empty() && storage.get_opt::<bool>("timers").await?.is_none() {
+            self.state.storage().put("timers", true).await?;
+            self.state.storage().put("fail_alarm", true).await?;
+            timers.set("second", Duration::from_millis(200)).await?;
+            timers.set("first", Duration::from_millis(100)).await?;
+            // Cancelling the earliest timer sets the alarm back to the next one.
+            timers.set("cancelled", Duration::from_millis(50)).await?;
+            assert!(timers.cancel("cancelled").await?);
+
+            let next = timers.get("first").await?;
+            assert!(
+                storage.get_alarm().await? == next,
+                "The alarm wasn't set to the earliest timer"
+            );
+        }
+
+        let fired = FiredTimers {
+            fired: storage.get_opt("fired").await?.unwrap_or_default(),
+            retry_count: storage.get_opt("timers_retry_count").await?,
+            is_retry: storage.get_opt("timers_is_retry").await?,
+        };
+        Ok(Response::new(serde_json::to_string(&fired)?.into()))
+    }
+}
@@ -0,0 +1,25 @@
+use betterworker::http::uri::PathAndQuery;
+use betterworker::http::{HeaderValue, StatusCode};
+use betterworker::prelude::*;
+
+/// Serves the single-page application in `public/spa`, in front of an API at
+/// `/spa/api`.
+pub async fn spa(req: Request<Body>, env: &Env) -> Result<Response<Body>, WorkerError> {
+    let server = AssetServer::new(env.assets("ASSETS")?)
+        .spa_fallback(PathAndQuery::from_static("/spa/"))
+        .cache_control(
+            "/spa/static/**",
+            HeaderValue::from_static("public, max-age=31536000, immutable"),
+        );
+
+    server
+        .handle(req, |req| async move {
+            if req.uri().path() == "/spa/api" {
+                return Ok(Response::new("api".into()));
+            }
+            let mut res = Response::new(Body::empty());
+            *res.status_mut() = StatusCode::NOT_FOUND;
+            Ok(res)
+        })
+        .await
+}
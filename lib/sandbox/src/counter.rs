@@ -1,41 +1,53 @@
 use betterworker::prelude::*;
-use betterworker::{durable_object, wasm_bindgen};
+use betterworker::durable_object;
 
 #[durable_object]
 pub struct Counter {
-    count: usize,
-    state: State,
-    initialized: bool,
+    #[persist]
+    count: Persisted<usize>,
     env: Env,
 }
 
 #[durable_object]
 impl DurableObject for Counter {
-    fn new(state: State, env: Env) -> Self {
+    fn new(_: State, env: Env) -> Self {
         Self {
-            count: 0,
-            initialized: false,
-            state,
+            count: Persisted::new(0),
             env,
         }
     }
 
-    async fn fetch(&mut self, _req: Request<Body>) -> Result<Response<Body>, WorkerError> {
-        if !self.initialized {
-            self.initialized = true;
-            self.count = self.state.storage().get("count").await.unwrap_or(0);
-        }
-
-        self.count += 10;
-        self.state.storage().put("count", self.count).await?;
+    async fn fetch(&self, _req: Request<Body>) -> Result<Response<Body>, WorkerError> {
+        let count = self.add(10).await?;
 
         Ok(Response::new(
             format!(
                 "[durable_object]: self.count: {}, secret value: {}",
-                self.count,
+                count,
                 self.env.secret("SOME_SECRET")?.to_string()
             )
             .into(),
         ))
     }
 }
+
+#[durable_object]
+impl Counter {
+    /// Adds `amount` to the count and returns the new count.
+    pub async fn increment(&self, amount: usize) -> Result<usize, WorkerError> {
+        self.add(amount).await
+    }
+}
+
+impl Counter {
+    /// The count is loaded on first use, and written back once the event
+    /// completes.
+    async fn add(&self, amount: usize) -> Result<usize, WorkerError> {
+        self.count
+            .update(|count| {
+                *count += amount;
+                *count
+            })
+            .await
+    }
+}
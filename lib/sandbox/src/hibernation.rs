@@ -0,0 +1,46 @@
+use betterworker::http::StatusCode;
+use betterworker::prelude::*;
+
+#[durable_object]
+pub struct EchoObject {
+    state: State,
+}
+
+#[durable_object]
+impl DurableObject for EchoObject {
+    fn new(state: State, _: Env) -> Self {
+        Self { state }
+    }
+
+    async fn fetch(&self, _: Request<Body>) -> Result<Response<Body>, WorkerError> {
+        let pair = WebSocketPair::new()?;
+        self.state.accept_web_socket(&pair.server, &["echo"])?;
+        pair.server.serialize_attachment(&0u32)?;
+        self.state.set_web_socket_auto_response("ping", "pong")?;
+
+        Ok(Response::builder()
+            .status(StatusCode::SWITCHING_PROTOCOLS)
+            .extension(pair.client)
+            .body(().into())
+            .unwrap())
+    }
+
+    async fn websocket_message(
+        &self, ws: WebSocket, message: WebSocketIncomingMessage,
+    ) -> Result<(), WorkerError> {
+        // The attachment survives hibernation, unlike the fields of `self`.
+        let count = ws.deserialize_attachment::<u32>()?.unwrap_or_default() + 1;
+        ws.serialize_attachment(&count)?;
+
+        match message {
+            WebSocketIncomingMessage::String(text) => ws.send_with_str(format!("{count} {text}")),
+            WebSocketIncomingMessage::Binary(bytes) => ws.send_with_bytes(bytes),
+        }
+    }
+
+    async fn websocket_close(
+        &self, ws: WebSocket, code: usize, reason: String, _: bool,
+    ) -> Result<(), WorkerError> {
+        ws.close(Some(code as u16), Some(reason))
+    }
+}
@@ -0,0 +1,80 @@
+//! Verifies tokens signed with the RSA key of [crate::web_crypto] against the
+//! key set this Worker serves itself.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use betterworker::auth::jwt::{Algorithm, JwtError};
+use betterworker::crypto::{self, CryptoKey, HashAlgorithm, KeyAlgorithm, KeyData, KeyFormat};
+use betterworker::date::Date;
+use betterworker::prelude::*;
+use serde_json::json;
+
+use crate::web_crypto::{key_pair, RSA_2048};
+
+const KID: &str = "sandbox";
+
+const RS256: KeyAlgorithm = KeyAlgorithm::RsaPkcs1 {
+    hash: HashAlgorithm::Sha256,
+};
+
+/// Serves the key set with the public key tokens are signed with.
+pub async fn jwks() -> Result<Response<Body>, WorkerError> {
+    let (_, public) = key_pair(RSA_2048, RS256).await?;
+    let KeyData::Jwk(mut jwk) = crypto::export_key(&public, KeyFormat::Jwk).await? else {
+        panic!("The key was not exported as a JWK");
+    };
+    jwk["kid"] = KID.into();
+    jwk["use"] = "sig".into();
+
+    let body = json!({ "keys": [jwk] });
+    Ok(Response::new(body.to_string().into()))
+}
+
+pub async fn verify(req: &Request<Body>) -> Result<(), WorkerError> {
+    let uri = req.uri();
+    let jwks_url = format!(
+        "{}://{}/jwt/jwks",
+        uri.scheme_str().unwrap(),
+        uri.authority().unwrap()
+    );
+    let verifier = JwtVerifier::new(jwks_url).algorithms(&[Algorithm::Rs256]);
+    let (private, _) = key_pair(RSA_2048, RS256).await?;
+    let exp = Date::now().as_millis() / 1000 + 60;
+
+    let token = sign(&private, KID, json!({ "sub": "user", "exp": exp })).await?;
+    let claims: Claims = verifier.verify(&token).await?;
+    assert_eq!(claims.sub.as_deref(), Some("user"));
+    assert_eq!(claims.exp, Some(exp));
+
+    // Swaps in the payload of another token, keeping the signature.
+    let other = sign(&private, KID, json!({ "sub": "admin", "exp": exp })).await?;
+    let mut parts = token.split('.').collect::<Vec<_>>();
+    parts[1] = other.split('.').nth(1).unwrap();
+    let tampered = parts.join(".");
+    assert!(matches!(
+        verifier.verify::<serde_json::Value>(&tampered).await,
+        Err(WorkerError::JwtError(JwtError::InvalidSignature))
+    ));
+
+    let unknown = sign(&private, "unknown", json!({ "sub": "user", "exp": exp })).await?;
+    assert!(matches!(
+        verifier.verify::<serde_json::Value>(&unknown).await,
+        Err(WorkerError::JwtError(JwtError::UnknownKey(Some(kid)))) if kid == "unknown"
+    ));
+    Ok(())
+}
+
+/// Signs `claims` into an RS256 token with the key ID `kid`.
+async fn sign(
+    key: &CryptoKey, kid: &str, claims: serde_json::Value,
+) -> Result<String, WorkerError> {
+    let header = json!({ "alg": "RS256", "typ": "JWT", "kid": kid });
+    let signed = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header.to_string()),
+        URL_SAFE_NO_PAD.encode(claims.to_string())
+    );
+    let signature =
+        crypto::sign(crypto::SignatureAlgorithm::RsaPkcs1, key, signed.as_bytes()).await?;
+    Ok(format!("{signed}.{}", URL_SAFE_NO_PAD.encode(signature)))
+}
@@ -5,15 +5,21 @@ use std::time::Duration;
 use betterworker::http::response::{from_web_sys_response, into_web_sys_response};
 use betterworker::http::{header, Method, StatusCode};
 use betterworker::prelude::*;
-use betterworker::{event, wasm_bindgen_futures};
+use betterworker::{crypto, event, wasm_bindgen_futures};
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 
 mod alarm;
+mod assets;
 mod counter;
+mod hibernation;
+mod jwt;
+mod primitives;
 mod r2;
+mod sql;
 mod test;
 mod utils;
+mod web_crypto;
 
 #[derive(Deserialize, Serialize)]
 struct MyData {
@@ -69,6 +75,10 @@ static GLOBAL_STATE: AtomicBool = AtomicBool::new(false);
 
 static GLOBAL_QUEUE_STATE: Mutex<Vec<QueueBody>> = Mutex::new(Vec::new());
 
+/// The URLs of the requests this Worker handled successfully, as reported to
+/// its own tail handler.
+static GLOBAL_TAIL_STATE: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
 // We're able to specify a start event that is called when the WASM is
 // initialized before any requests. This is useful if you have some global state
 // or setup code, like a logger. This is only called once for the entire
@@ -210,6 +220,56 @@ pub async fn main(req: Request<Body>, env: Env, _ctx: Context) -> Result<Respons
             // wrangler.toml to opt-in to older behavior: https://developers.cloudflare.com/workers/platform/compatibility-dates#durable-object-stubfetch-requires-a-full-url
             stub.fetch_with_str("https://fake-host/").await?
         },
+        (Method::GET, "/durable/rpc") => {
+            let namespace = env.durable_object("COUNTER")?;
+            let counter = counter::CounterStub::from(namespace.id_from_name("rpc")?.get_stub()?);
+            let first = counter.increment(5).await?;
+            let second = counter.increment(5).await?;
+            Response::new((second - first).to_string().into())
+        },
+        (Method::GET, "/durable/concurrent") => {
+            let namespace = env.durable_object("COUNTER")?;
+            let counter = counter::CounterStub::from(namespace.id_from_name("concurrent")?.get_stub()?);
+            let before = counter.increment(0).await?;
+            // The calls interleave at their storage writes inside the object.
+            let (a, b, c) = futures_util::future::try_join3(
+                counter.increment(1),
+                counter.increment(1),
+                counter.increment(1),
+            )
+            .await?;
+            let mut counts = [a - before, b - before, c - before];
+            counts.sort_unstable();
+            assert_eq!(counts, [1, 2, 3], "Concurrent calls lost an increment");
+            Response::new(counts[2].to_string().into())
+        },
+        (Method::GET, "/durable/placement") => {
+            let namespace = env.durable_object("COUNTER")?;
+            let id = namespace.id_from_name("placement")?;
+            assert!(id == namespace.id_from_name("placement")?);
+            assert!(id != namespace.id_from_name("elsewhere")?);
+            assert_eq!(id.name().as_deref(), Some("placement"));
+
+            let stub = id.get_stub_with_options(GetStubOptions {
+                location_hint: Some(LocationHint::Weur),
+            })?;
+            let first = counter::CounterStub::from(stub).increment(1).await?;
+            let stub = namespace.get_by_name("placement")?;
+            assert!(stub.id() == id);
+
+            // Ids serialize as their hex string, which parses back into the same id.
+            let json = serde_json::to_string(&id)?;
+            let hex: String = serde_json::from_str(&json)?;
+            assert!(namespace.id_from_string(&hex)? == id);
+            let parsed = serde::de::DeserializeSeed::deserialize(
+                namespace.id_seed(),
+                &mut serde_json::Deserializer::from_str(&json),
+            )?;
+            assert!(parsed == id);
+
+            let second = counter::CounterStub::from(stub).increment(1).await?;
+            Response::new((second - first).to_string().into())
+        },
         (Method::GET, "/durable/alarm") => {
             let namespace = env.durable_object("ALARM")?;
             let stub = namespace.id_from_name("alarm")?.get_stub()?;
@@ -218,6 +278,185 @@ pub async fn main(req: Request<Body>, env: Env, _ctx: Context) -> Result<Respons
             // wrangler.toml to opt-in to older behavior: https://developers.cloudflare.com/workers/platform/compatibility-dates#durable-object-stubfetch-requires-a-full-url
             stub.fetch_with_str("https://fake-host/alarm").await?
         },
+        (Method::POST, "/durable/timers") => {
+            let name = utils::fresh_name("timers");
+            let namespace = env.durable_object("ALARM")?;
+            namespace
+                .get_by_name(&name)?
+                .fetch_with_str("https://fake-host/timers")
+                .await?;
+            Response::new(name.into())
+        },
+        (Method::GET, "/durable/timers") => {
+            let name = req
+                .uri()
+                .query()
+                .and_then(|query| query.strip_prefix("name="))
+                .unwrap_or_default();
+            let namespace = env.durable_object("ALARM")?;
+            let stub = namespace.get_by_name(name)?;
+            stub.fetch_with_str("https://fake-host/timers").await?
+        },
+        (Method::GET, "/durable/transaction") => {
+            let namespace = env.durable_object("MY_CLASS")?;
+            let stub = namespace.id_from_name("transaction")?.get_stub()?;
+            stub.fetch_with_str("https://fake-host/transaction").await?
+        },
+        (Method::GET, "/durable/transaction/rollback") => {
+            let namespace = env.durable_object("MY_CLASS")?;
+            let stub = namespace.id_from_name("transaction")?.get_stub()?;
+            stub.fetch_with_str("https://fake-host/transaction/rollback").await?
+        },
+        (Method::GET, "/durable/sql") => {
+            let namespace = env.durable_object("SQL")?;
+            let stub = namespace.id_from_name("sql")?.get_stub()?;
+            stub.fetch_with_str("https://fake-host/sql").await?
+        },
+        (Method::GET, "/durable/bookmark") => {
+            let namespace = env.durable_object("SQL")?;
+            let stub = namespace.id_from_name("sql")?.get_stub()?;
+            stub.fetch_with_str("https://fake-host/bookmark").await?
+        },
+        (Method::GET, "/durable/lifecycle") => {
+            let namespace = env.durable_object("MY_CLASS")?;
+            let stub = namespace.get_by_name("lifecycle")?;
+            stub.fetch_with_str("https://fake-host/lifecycle").await?
+        },
+        (Method::GET, "/durable/abort") => {
+            let namespace = env.durable_object("MY_CLASS")?;
+            let aborted = namespace
+                .get_by_name("abort")?
+                .fetch_with_str("https://fake-host/abort")
+                .await;
+            assert!(aborted.is_err(), "The object was not aborted");
+
+            // The next request reaches a new instance of the object.
+            let stub = namespace.get_by_name("abort")?;
+            stub.fetch_with_str("https://fake-host/hello").await?
+        },
+        (Method::GET, "/durable/persist") => {
+            let namespace = env.durable_object("MY_CLASS")?;
+            let name = utils::fresh_name("persist");
+            namespace
+                .get_by_name(&name)?
+                .fetch_with_str("https://fake-host/persist/write")
+                .await?;
+            // Evict the instance, so that the field is read back from storage.
+            let aborted = namespace
+                .get_by_name(&name)?
+                .fetch_with_str("https://fake-host/abort")
+                .await;
+            assert!(aborted.is_err(), "The object was not aborted");
+            namespace
+                .get_by_name(&name)?
+                .fetch_with_str("https://fake-host/persist/read")
+                .await?
+        },
+        (Method::GET, "/durable/retry/retryable") => {
+            let namespace = env.durable_object("MY_CLASS")?;
+            let stub = namespace
+                .get_by_name(&utils::fresh_name("retryable"))?
+                .with_retry(RetryOptions {
+                    max_attempts: 3,
+                    base_delay: Duration::from_millis(10),
+                    max_delay: Duration::from_millis(100),
+                });
+            let req = Request::builder()
+                .uri("https://fake-host/flaky/retryable")
+                .body(Body::empty())
+                .unwrap();
+            // The third attempt succeeds and returns the number of attempts.
+            stub.fetch_with_request(req).await?
+        },
+        (Method::GET, "/durable/retry/overloaded") => {
+            let namespace = env.durable_object("MY_CLASS")?;
+            let stub = namespace
+                .get_by_name(&utils::fresh_name("overloaded"))?
+                .with_retry(RetryOptions::default());
+            let req = Request::builder()
+                .uri("https://fake-host/flaky/overloaded")
+                .body(Body::empty())
+                .unwrap();
+            match stub.fetch_with_request(req).await {
+                Err(WorkerError::DurableObject(err)) => {
+                    assert!(err.overloaded && !err.retryable, "Wrong flags: {err:?}");
+                    assert!(!err.should_retry());
+                },
+                other => panic!("Expected an overloaded error, got {:?}", other.map(|_| ())),
+            }
+            // Overloaded objects are not retried.
+            stub.fetch_with_str("https://fake-host/flaky/attempts")
+                .await?
+        },
+        (Method::GET, "/durable/retry") => {
+            let namespace = env.durable_object("MY_CLASS")?;
+            let stub = namespace
+                .get_by_name("retry")?
+                .with_retry(RetryOptions::default());
+            let req = Request::builder()
+                .uri("https://fake-host/hello")
+                .body(Body::empty())
+                .unwrap();
+            stub.fetch_with_request(req).await?
+        },
+        (Method::GET, "/durable/primitives/lock") => {
+            primitives::lock(&env).await?;
+            Response::new("ok".into())
+        },
+        (Method::GET, "/durable/primitives/semaphore") => {
+            primitives::semaphore(&env).await?;
+            Response::new("ok".into())
+        },
+        (Method::GET, "/durable/primitives/counter") => {
+            primitives::counter(&env).await?;
+            Response::new("ok".into())
+        },
+        (Method::GET, "/durable/primitives/lease") => {
+            primitives::lease(&env).await?;
+            Response::new("ok".into())
+        },
+        (Method::GET, "/durable/websocket") => {
+            let namespace = env.durable_object("ECHO")?;
+            let stub = namespace.id_from_name("echo")?.get_stub()?;
+            stub.fetch_with_request(req).await?
+        },
+        (Method::GET, "/rpc/entrypoint") => {
+            let calculator = env.service("CALCULATOR")?;
+            let sum: i64 = calculator.call("add", (2, 3)).await?;
+            assert_eq!(sum, 5);
+            // A `Vec` is passed as a single argument rather than spread.
+            let total: i64 = calculator.call("sum", (vec![1, 2, 3],)).await?;
+            assert_eq!(total, 6);
+            let echoed = calculator
+                .rpc("echo")
+                .body("echo")
+                .call_body()
+                .await?;
+            Response::new(echoed)
+        },
+        (Method::POST, "/workflow") => {
+            let workflow = env.workflow("COUNTING_WORKFLOW")?;
+            let instance = workflow
+                .create(&serde_json::json!({ "count": 5 }))
+                .await?;
+            instance.send_event("bonus", &10).await?;
+            Response::new(instance.id().into())
+        },
+        (Method::GET, "/workflow") => {
+            let id = req
+                .uri()
+                .query()
+                .and_then(|query| query.strip_prefix("id="))
+                .unwrap_or_default();
+            let workflow = env.workflow("COUNTING_WORKFLOW")?;
+            let status = workflow.get(id).await?.status().await?;
+            let body = serde_json::json!({
+                "complete": status.status == InstanceState::Complete,
+                "output": status.output,
+                "error": status.error,
+            });
+            Response::new(body.to_string().into())
+        },
         (Method::GET, "/service-binding") => {
             let fetcher = env.service("remote")?;
             fetcher.fetch(req).await?
@@ -237,6 +476,11 @@ pub async fn main(req: Request<Body>, env: Env, _ctx: Context) -> Result<Respons
             let messages: Vec<QueueBody> = guard.clone();
             Response::new(serde_json::to_string(&messages).unwrap().into())
         },
+        (Method::GET, "/tail/ping") => Response::new("pong".into()),
+        (Method::GET, "/tail") => {
+            let urls = GLOBAL_TAIL_STATE.lock().unwrap().clone();
+            Response::new(serde_json::to_string(&urls).unwrap().into())
+        },
         (Method::GET, "/r2/list-empty") => r2::list_empty(&env).await?,
         (Method::GET, "/r2/list") => r2::list(&env).await?,
         (Method::GET, "/r2/get-empty") => r2::get_empty(&env).await?,
@@ -332,6 +576,29 @@ pub async fn main(req: Request<Body>, env: Env, _ctx: Context) -> Result<Respons
                 .unwrap();
             Response::new(serde_json::to_string(&new_person).unwrap().into())
         },
+        (Method::POST, "/crypto/digest") => {
+            let data = req.into_body().bytes().await?;
+            let digest = crypto::digest(crypto::DigestAlgorithm::Sha256, &data).await?;
+            let hex = digest.iter().map(|b| format!("{b:02x}")).collect::<String>();
+            Response::new(hex.into())
+        },
+        (Method::POST, "/crypto/digest-stream") => {
+            let (body, digest) =
+                crypto::digest_body(crypto::DigestAlgorithm::Sha256, req.into_body())?;
+            let len = body.bytes().await?.len();
+            let hex = digest.await?.iter().map(|b| format!("{b:02x}")).collect::<String>();
+            Response::new(format!("{len} {hex}").into())
+        },
+        (Method::GET, "/jwt/jwks") => jwt::jwks().await?,
+        (Method::GET, "/jwt/verify") => {
+            jwt::verify(&req).await?;
+            Response::new("ok".into())
+        },
+        (Method::GET, path) if path.starts_with("/crypto/") => {
+            web_crypto::run(&path["/crypto/".len()..]).await?;
+            Response::new("ok".into())
+        },
+        (Method::GET, path) if path.starts_with("/spa/") => assets::spa(req, &env).await?,
         _ => panic!("unknown uri {}", req.uri()),
     };
 
@@ -359,3 +626,19 @@ pub async fn queue(
     }
     Ok(())
 }
+
+#[event(tail)]
+pub async fn tail(items: Vec<TraceItem>, _env: Env, _ctx: Context) -> Result<(), WorkerError> {
+    let mut guard = GLOBAL_TAIL_STATE.lock().unwrap();
+    for item in items {
+        console_log!(
+            "Received trace item for {:?} with outcome {}",
+            item.script_name,
+            item.outcome
+        );
+        if let (TraceOutcome::Ok, Some(TraceEvent::Fetch(info))) = (&item.outcome, &item.event) {
+            guard.push(info.request.url.clone());
+        }
+    }
+    Ok(())
+}
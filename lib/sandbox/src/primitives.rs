@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use betterworker::durable::primitives::{
+    DurableCounterStub, DurableLockStub, DurableSemaphoreStub, LeaderLeaseStub,
+};
+use betterworker::prelude::*;
+
+use crate::utils::fresh_name;
+
+pub async fn lock(env: &Env) -> Result<(), WorkerError> {
+    let namespace = env.durable_object("LOCK")?;
+    let lock = DurableLockStub::from(namespace.get_by_name(&fresh_name("lock"))?);
+
+    assert!(lock.acquire("a".into(), Duration::from_secs(60)).await?);
+    assert!(!lock.acquire("b".into(), Duration::from_secs(60)).await?);
+    assert!(lock.acquire("a".into(), Duration::from_millis(100)).await?);
+    assert_eq!(
+        lock.holder().await?.map(|holder| holder.owner).as_deref(),
+        Some("a")
+    );
+    assert!(!lock.release("b".into()).await?);
+    assert!(lock.release("a".into()).await?);
+    assert_eq!(lock.holder().await?, None);
+
+    // A holder that never releases the lock loses it once it expires.
+    assert!(lock.acquire("b".into(), Duration::from_millis(100)).await?);
+    Delay::from(Duration::from_millis(300)).await;
+    assert_eq!(lock.holder().await?, None);
+    assert!(lock.acquire("a".into(), Duration::from_secs(60)).await?);
+    Ok(())
+}
+
+pub async fn semaphore(env: &Env) -> Result<(), WorkerError> {
+    let namespace = env.durable_object("SEMAPHORE")?;
+    let semaphore = DurableSemaphoreStub::from(namespace.get_by_name(&fresh_name("semaphore"))?);
+
+    assert!(
+        semaphore
+            .acquire("a".into(), 2, Duration::from_secs(60))
+            .await?
+    );
+    assert!(
+        semaphore
+            .acquire("b".into(), 2, Duration::from_millis(100))
+            .await?
+    );
+    assert!(
+        !semaphore
+            .acquire("c".into(), 2, Duration::from_secs(60))
+            .await?
+    );
+    // Holders renew their permit even when all permits are held.
+    assert!(
+        semaphore
+            .acquire("a".into(), 2, Duration::from_secs(60))
+            .await?
+    );
+
+    Delay::from(Duration::from_millis(300)).await;
+    let holders: Vec<_> = semaphore
+        .permits()
+        .await?
+        .into_iter()
+        .map(|permit| permit.holder)
+        .collect();
+    assert_eq!(holders, ["a"]);
+    assert!(
+        semaphore
+            .acquire("c".into(), 2, Duration::from_secs(60))
+            .await?
+    );
+
+    assert!(semaphore.release("a".into()).await?);
+    assert!(!semaphore.release("a".into()).await?);
+    assert_eq!(semaphore.permits().await?.len(), 1);
+    Ok(())
+}
+
+pub async fn counter(env: &Env) -> Result<(), WorkerError> {
+    let namespace = env.durable_object("ATOMIC_COUNTER")?;
+    let counter = DurableCounterStub::from(namespace.get_by_name(&fresh_name("counter"))?);
+
+    assert_eq!(counter.get().await?, 0);
+    let (a, b) = futures_util::future::try_join(counter.add(2), counter.add(3)).await?;
+    assert_eq!(a.max(b), 5);
+    assert_eq!(counter.add(-10).await?, -5);
+    assert_eq!(counter.set(7).await?, -5);
+    assert!(!counter.compare_and_swap(6, 1).await?);
+    assert!(counter.compare_and_swap(7, 1).await?);
+    assert_eq!(counter.get().await?, 1);
+    assert!(counter.add(i64::MAX).await.is_err());
+    assert_eq!(counter.get().await?, 1);
+    Ok(())
+}
+
+pub async fn lease(env: &Env) -> Result<(), WorkerError> {
+    let namespace = env.durable_object("LEASE")?;
+    let lease = LeaderLeaseStub::from(namespace.get_by_name(&fresh_name("lease"))?);
+
+    let first = lease
+        .campaign("a".into(), Duration::from_millis(100))
+        .await?;
+    assert!(first.is_held_by("a"));
+    assert_eq!(first.term, 1);
+    let lost = lease.campaign("b".into(), Duration::from_secs(60)).await?;
+    assert_eq!(lost, first);
+
+    // The leader keeps its term when it renews its lease.
+    let renewed = lease
+        .campaign("a".into(), Duration::from_millis(100))
+        .await?;
+    assert_eq!(renewed.term, 1);
+    assert!(renewed.expires_at >= first.expires_at);
+
+    Delay::from(Duration::from_millis(300)).await;
+    assert_eq!(lease.leader().await?, None);
+    let second = lease.campaign("b".into(), Duration::from_secs(60)).await?;
+    assert!(second.is_held_by("b"));
+    assert_eq!(second.term, 2);
+
+    assert!(!lease.resign("a".into()).await?);
+    assert!(lease.resign("b".into()).await?);
+    assert_eq!(
+        lease
+            .campaign("a".into(), Duration::from_secs(60))
+            .await?
+            .term,
+        3
+    );
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use betterworker::prelude::*;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Visit {
+    id: i64,
+    path: String,
+}
+
+#[durable_object]
+pub struct SqlObject {
+    state: State,
+}
+
+#[durable_object]
+impl DurableObject for SqlObject {
+    fn new(state: State, _: Env) -> Self {
+        let sql = state.storage().sql();
+        sql.exec::<()>(
+            "CREATE TABLE IF NOT EXISTS visits (id INTEGER PRIMARY KEY, path TEXT NOT NULL)",
+            &[],
+        )
+        .expect("failed to create table");
+
+        Self { state }
+    }
+
+    async fn fetch(&self, req: Request<Body>) -> Result<Response<Body>, WorkerError> {
+        if req.uri().path() == "/bookmark" {
+            return self.bookmark().await;
+        }
+
+        let storage = self.state.storage();
+        let sql = storage.sql();
+
+        let inserted = sql.exec::<()>("INSERT INTO visits (path) VALUES (?)", &[req
+            .uri()
+            .path()
+            .into()])?;
+        assert!(inserted.rows_written() == 1, "Insert did not write a row");
+
+        let visits = sql
+            .exec::<Visit>("SELECT id, path FROM visits ORDER BY id", &[])?
+            .collect::<Result<Vec<_>, _>>()?;
+        let last = visits.last().expect("Visit was not inserted");
+        assert!(last.path == req.uri().path(), "Wrong path was inserted");
+
+        let cursor = sql.exec::<Visit>("SELECT id, path FROM visits WHERE id = ?", &[last
+            .id
+            .into()])?;
+        assert!(
+            cursor.column_names() == ["id", "path"],
+            "Wrong column names"
+        );
+        assert!(cursor.one()?.id == last.id, "one() returned the wrong row");
+
+        let raw = sql
+            .exec::<()>("SELECT COUNT(*), MAX(id) FROM visits", &[])?
+            .raw::<(usize, i64)>()?
+            .next()
+            .expect("Aggregate returned no row")?;
+        assert!(
+            raw == (visits.len(), last.id),
+            "raw() returned the wrong row"
+        );
+
+        let result =
+            storage.transaction_sync(|| Err::<(), _>(WorkerError::JsError("rollback".into())));
+        assert!(result.is_err(), "transaction_sync did not return the error");
+
+        let mut kv = storage.kv();
+        let count = storage.transaction_sync(move || {
+            let count = kv.get::<usize>("count")?.unwrap_or(0) + 1;
+            kv.put("count", count)?;
+            Ok(count)
+        })?;
+        assert!(count == visits.len(), "Sync KV count is out of sync");
+        assert!(sql.database_size() > 0, "Database is empty");
+
+        Ok(Response::new(count.to_string().into()))
+    }
+}
+
+impl SqlObject {
+    /// Checks that bookmarks can be taken, without restoring any of them.
+    async fn bookmark(&self) -> Result<Response<Body>, WorkerError> {
+        let storage = self.state.storage();
+        let earlier = storage
+            .bookmark_for_time(DateInit::Millis(Date::now().as_millis() - 1000))
+            .await?;
+        let current = storage.current_bookmark().await?;
+        assert!(earlier <= current, "Bookmarks are out of order");
+
+        let parsed: Bookmark = serde_json::from_str(&serde_json::to_string(&current)?)?;
+        assert!(parsed == current, "Bookmark did not roundtrip");
+
+        Ok(Response::new("ok".into()))
+    }
+}
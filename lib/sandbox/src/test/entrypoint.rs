@@ -0,0 +1,22 @@
+use betterworker::prelude::*;
+
+pub struct Calculator;
+
+#[entrypoint]
+impl Calculator {
+    pub fn new(_ctx: Context, _env: Env) -> Self {
+        Self
+    }
+
+    pub async fn add(&self, a: i64, b: i64) -> Result<i64, WorkerError> {
+        Ok(a + b)
+    }
+
+    pub async fn sum(&self, values: Vec<i64>) -> Result<i64, WorkerError> {
+        Ok(values.into_iter().sum())
+    }
+
+    pub async fn echo(&self, body: Body) -> Result<Body, WorkerError> {
+        Ok(body)
+    }
+}
@@ -1,26 +1,102 @@
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
 
 use betterworker::http::StatusCode;
 use betterworker::prelude::*;
-use betterworker::wasm_bindgen;
+use futures_util::TryStreamExt;
 use serde::Serialize;
 
 #[durable_object]
 pub struct MyClass {
     state: State,
-    number: usize,
+    number: Cell<usize>,
+    #[persist(key = "persisted-visits")]
+    visits: Persisted<BTreeMap<u32, String>, JsonCodec>,
 }
 
 #[durable_object]
 impl DurableObject for MyClass {
     fn new(state: State, _env: Env) -> Self {
-        Self { state, number: 0 }
+        Self {
+            state,
+            number: Cell::new(0),
+            visits: Persisted::default(),
+        }
     }
 
-    async fn fetch(&mut self, req: Request<Body>) -> Result<Response<Body>, WorkerError> {
+    async fn fetch(&self, req: Request<Body>) -> Result<Response<Body>, WorkerError> {
+        // Errors of these paths reach the caller rather than becoming a 500.
+        if let Some(flag) = req.uri().path().strip_prefix("/flaky/") {
+            return self.flaky(flag).await;
+        }
+
         let handler = async move {
             match req.uri().path() {
                 "/hello" => Ok::<_, WorkerError>(Response::new("Hello!".into())),
+                "/persist/write" => {
+                    self.visits
+                        .update(|visits| visits.insert(1, "one".into()))
+                        .await?;
+                    Ok(Response::new("ok".into()))
+                },
+                "/persist/read" => {
+                    // A new instance of the object loads the field from storage.
+                    let visits = self.visits.get().await?;
+                    assert!(
+                        visits == BTreeMap::from([(1, "one".to_string())]),
+                        "The persisted field was not reloaded"
+                    );
+                    let stored: String = self.state.storage().get("persisted-visits").await?;
+                    assert!(
+                        stored == r#"{"1":"one"}"#,
+                        "The field was not stored as JSON"
+                    );
+                    Ok(Response::new("ok".into()))
+                },
+                "/lifecycle" => {
+                    let storage = self.state.storage();
+                    let number = self
+                        .state
+                        .block_concurrency_while(
+                            async move { storage.get_opt::<usize>("count").await },
+                        )
+                        .await?;
+                    assert!(
+                        number == self.state.storage().get_opt("count").await?,
+                        "block_concurrency_while returned the wrong value"
+                    );
+
+                    let failed = self
+                        .state
+                        .block_concurrency_while(async {
+                            Err::<(), _>(WorkerError::JsError("init".into()))
+                        })
+                        .await;
+                    assert!(
+                        matches!(failed, Err(WorkerError::JsError(ref msg)) if msg == "init"),
+                        "block_concurrency_while did not return the error of its future"
+                    );
+
+                    let mut storage = self.state.storage();
+                    self.state.wait_until(async move {
+                        let _ = storage.put("waited", true).await;
+                    });
+
+                    assert!(
+                        self.state.class_name() == Some("MyClass"),
+                        "Wrong class name"
+                    );
+                    let namespace = self.state.namespace()?;
+                    assert!(
+                        namespace.id_from_name("lifecycle")? == self.state.id(),
+                        "The namespace of the object is not its own"
+                    );
+                    Ok(Response::new("ok".into()))
+                },
+                "/abort" => {
+                    self.state.abort("reset by /abort")?;
+                    Ok(Response::new("not aborted".into()))
+                },
                 "/storage" => {
                     let mut storage = self.state.storage();
                     let map = [("one".to_string(), 1), ("two".to_string(), 2)]
@@ -31,13 +107,8 @@ impl DurableObject for MyClass {
                     storage.put("array", [("one", 1), ("two", 2)]).await?;
                     storage.put("anything", Some(45)).await?;
 
-                    let list = storage.list().await?;
-                    let mut keys = vec![];
-
-                    for key in list.keys() {
-                        let key = key.unwrap().as_string().expect("Key wasn't a string");
-                        keys.push(key);
-                    }
+                    let list = storage.list::<serde_json::Value>().await?;
+                    let keys = list.into_keys().collect::<Vec<_>>();
 
                     assert!(
                         keys == vec!["anything", "array", "map"],
@@ -67,6 +138,62 @@ impl DurableObject for MyClass {
                         "Didn't get the right HashMap<String, i32> using get_multiple"
                     );
 
+                    let vals = storage
+                        .get_many::<Option<i32>>(vec!["anything", "missing"])
+                        .await?;
+                    assert!(
+                        vals.len() == 1 && vals["anything"] == Some(45),
+                        "Didn't get the right values using get_many: {vals:?}"
+                    );
+                    assert!(
+                        storage.get_opt::<i32>("missing").await?.is_none(),
+                        "Didn't get None for a missing key using get_opt"
+                    );
+
+                    storage
+                        .put_with_options("unconfirmed", 1, PutOptions {
+                            allow_unconfirmed: Some(true),
+                            ..Default::default()
+                        })
+                        .await?;
+                    storage.sync().await?;
+                    let unconfirmed = storage
+                        .get_with_options::<i32>("unconfirmed", GetOptions {
+                            no_cache: Some(true),
+                            ..Default::default()
+                        })
+                        .await?;
+                    assert!(
+                        unconfirmed == Some(1),
+                        "Didn't get the value put with allow_unconfirmed"
+                    );
+
+                    let mut users = storage.namespace::<i32>("user:");
+                    for (key, value) in [("a", 1), ("b", 2), ("c", 3)] {
+                        users.put(key, &value).await?;
+                    }
+                    let listed = users.list().await?.into_iter().collect::<Vec<_>>();
+                    assert!(
+                        listed == [("a".into(), 1), ("b".into(), 2), ("c".into(), 3)],
+                        "Didn't list the namespace: {listed:?}"
+                    );
+                    let streamed: Vec<(String, i32)> = users
+                        .list_stream(ListOptions::new().reverse(true), 2)
+                        .try_collect()
+                        .await?;
+                    assert!(
+                        streamed == [("c".into(), 3), ("b".into(), 2), ("a".into(), 1)],
+                        "Didn't stream the namespace: {streamed:?}"
+                    );
+                    let limited: Vec<(String, i32)> = storage
+                        .list_stream(ListOptions::new().prefix("user:").limit(3), 2)
+                        .try_collect()
+                        .await?;
+                    assert!(
+                        limited.len() == 3 && limited[2].0 == "user:c",
+                        "Didn't stream with a limit: {limited:?}"
+                    );
+
                     #[derive(Serialize)]
                     struct Stuff {
                         thing: String,
@@ -90,17 +217,63 @@ impl DurableObject for MyClass {
 
                     storage.delete_multiple(vec!["thing", "other"]).await?;
 
-                    self.number = storage.get("count").await.unwrap_or(0) + 1;
+                    let number = storage.get("count").await.unwrap_or(0) + 1;
+                    self.number.set(number);
 
                     storage.delete_all().await?;
 
-                    storage.put("count", self.number).await?;
-                    Ok(Response::new(self.number.to_string().into()))
+                    storage.put("count", number).await?;
+                    Ok(Response::new(number.to_string().into()))
+                },
+                "/transaction" => {
+                    let count = self
+                        .state
+                        .storage()
+                        .transaction(|mut txn| async move {
+                            let count = txn.get::<usize>("count").await.unwrap_or(0) + 1;
+                            txn.put("count", count).await?;
+                            Ok(count)
+                        })
+                        .await?;
+                    self.number.set(count);
+                    Ok(Response::new(count.to_string().into()))
+                },
+                "/transaction/rollback" => {
+                    let storage = self.state.storage();
+
+                    let failed = storage
+                        .transaction(|mut txn| async move {
+                            txn.put("failed", true).await?;
+                            Err::<(), _>(WorkerError::JsError("abort".into()))
+                        })
+                        .await;
+                    assert!(
+                        matches!(failed, Err(WorkerError::JsError(ref msg)) if msg == "abort"),
+                        "Transaction did not return the error of its closure"
+                    );
+                    assert!(
+                        storage.get::<bool>("failed").await.is_err(),
+                        "Transaction returning Err was not rolled back"
+                    );
+
+                    let value = storage
+                        .transaction(|mut txn| async move {
+                            txn.put("rolled-back", true).await?;
+                            txn.rollback()?;
+                            Ok("value")
+                        })
+                        .await?;
+                    assert!(
+                        value == "value",
+                        "Transaction did not return the result of its closure"
+                    );
+                    assert!(
+                        storage.get::<bool>("rolled-back").await.is_err(),
+                        "Transaction calling rollback() was not rolled back"
+                    );
+
+                    Ok(Response::new("ok".into()))
                 },
-                "/transaction" => Ok(Response::builder()
-                    .status(StatusCode::NOT_IMPLEMENTED)
-                    .body("transactional storage API is still unstable".into())
-                    .unwrap()),
                 _ => Ok(Response::builder()
                     .status(StatusCode::NOT_FOUND)
                     .body("Not Found".into())
@@ -115,3 +288,27 @@ impl DurableObject for MyClass {
         })
     }
 }
+
+impl MyClass {
+    /// Counts the attempts, and fails the first two with a
+    /// [`DurableObjectError`] carrying `flag`. `attempts` only returns the
+    /// count.
+    async fn flaky(&self, flag: &str) -> Result<Response<Body>, WorkerError> {
+        let mut storage = self.state.storage();
+        let mut attempts = storage.get_opt::<u32>("flaky-attempts").await?.unwrap_or(0);
+        if flag != "attempts" {
+            attempts += 1;
+            storage.put("flaky-attempts", attempts).await?;
+            if attempts < 3 {
+                return Err(DurableObjectError {
+                    message: format!("attempt {attempts} failed"),
+                    retryable: flag == "retryable",
+                    overloaded: flag == "overloaded",
+                    remote: true,
+                }
+                .into());
+            }
+        }
+        Ok(Response::new(attempts.to_string().into()))
+    }
+}
@@ -1,2 +1,4 @@
 pub mod durable;
+pub mod entrypoint;
 pub mod export_durable_object;
+pub mod workflow;
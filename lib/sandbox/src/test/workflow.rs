@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use betterworker::prelude::*;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Params {
+    count: u32,
+}
+
+pub struct CountingWorkflow;
+
+#[workflow]
+impl CountingWorkflow {
+    pub fn new(_ctx: Context, _env: Env) -> Self {
+        Self
+    }
+
+    pub async fn run(
+        &self, event: WorkflowEvent<Params>, step: WorkflowStep,
+    ) -> Result<u32, WorkerError> {
+        let count = event.payload.count;
+        let config = StepConfig::new()
+            .retries(3, Duration::from_secs(1), Backoff::Exponential)
+            .timeout(Duration::from_secs(30));
+        let doubled: u32 = step
+            .do_with_config("double", config, move || async move { Ok(count * 2) })
+            .await?;
+
+        step.sleep("pause", Duration::from_secs(1)).await?;
+        let until = Date::new(DateInit::Millis(Date::now().as_millis() + 1000));
+        step.sleep_until("pause until", until).await?;
+
+        let bonus = step
+            .wait_for_event::<u32>("bonus", "bonus", Some(Duration::from_secs(60)))
+            .await?;
+        let bonus = bonus.payload;
+        step.do_("increment", move || async move { Ok(doubled + 1 + bonus) })
+            .await
+    }
+}
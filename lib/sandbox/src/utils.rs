@@ -1,3 +1,4 @@
+use betterworker::date::Date;
 use cfg_if::cfg_if;
 
 cfg_if! {
@@ -10,3 +11,9 @@ cfg_if! {
         pub fn set_panic_hook() {}
     }
 }
+
+/// A name no earlier run of the tests has used, so that each run starts from
+/// a fresh object.
+pub fn fresh_name(prefix: &str) -> String {
+    format!("{prefix}-{}", Date::now().as_millis())
+}
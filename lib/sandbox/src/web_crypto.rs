@@ -0,0 +1,231 @@
+//! Round trips through the Web Crypto API. The keys in `keys/` were generated
+//! for these tests only.
+
+use betterworker::crypto::{
+    self, CryptoKey, DeriveAlgorithm, HashAlgorithm, KeyAlgorithm, KeyData, KeyFormat, KeyUsage,
+    NamedCurve, SignatureAlgorithm,
+};
+use betterworker::prelude::*;
+
+const EC_P256: &[u8] = include_bytes!("keys/ec-p256.pk8");
+const ED25519: &[u8] = include_bytes!("keys/ed25519.pk8");
+pub const RSA_2048: &[u8] = include_bytes!("keys/rsa-2048.pk8");
+
+const DATA: &[u8] = b"betterworker";
+
+pub async fn run(name: &str) -> Result<(), WorkerError> {
+    match name {
+        "hmac" => hmac().await,
+        "ecdsa" => ecdsa().await,
+        "ed25519" => ed25519().await,
+        "rsa-pss" => rsa_pss().await,
+        "aes-gcm" => aes_gcm().await,
+        "pkcs8" => pkcs8().await,
+        "hkdf" => hkdf().await,
+        "pbkdf2" => pbkdf2().await,
+        "timing-safe-equal" => timing_safe_equal(),
+        _ => panic!("unknown crypto test {name}"),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Imports a private key, and the public key of its JWK without the private
+/// parameters.
+pub async fn key_pair(
+    pkcs8: &[u8], algorithm: KeyAlgorithm,
+) -> Result<(CryptoKey, CryptoKey), WorkerError> {
+    let private = crypto::import_key(KeyData::Pkcs8(pkcs8.to_vec()), algorithm, true, &[
+        KeyUsage::Sign,
+    ])
+    .await?;
+    let KeyData::Jwk(mut jwk) = crypto::export_key(&private, KeyFormat::Jwk).await? else {
+        panic!("The key was not exported as a JWK");
+    };
+    let params = jwk.as_object_mut().unwrap();
+    for param in ["d", "p", "q", "dp", "dq", "qi", "key_ops"] {
+        params.remove(param);
+    }
+    let public =
+        crypto::import_key(KeyData::Jwk(jwk), algorithm, true, &[KeyUsage::Verify]).await?;
+    Ok((private, public))
+}
+
+/// Signs [DATA], and checks the signature only verifies for it.
+async fn sign_and_verify(
+    algorithm: SignatureAlgorithm, private: &CryptoKey, public: &CryptoKey,
+) -> Result<Vec<u8>, WorkerError> {
+    let signature = crypto::sign(algorithm, private, DATA).await?;
+    assert!(crypto::verify(algorithm, public, &signature, DATA).await?);
+    assert!(!crypto::verify(algorithm, public, &signature, b"tampered").await?);
+    Ok(signature)
+}
+
+async fn hmac() -> Result<(), WorkerError> {
+    // RFC 4231, test case 2.
+    let key = crypto::import_key(
+        KeyData::Raw(b"Jefe".to_vec()),
+        KeyAlgorithm::Hmac {
+            hash: HashAlgorithm::Sha256,
+        },
+        false,
+        &[KeyUsage::Sign, KeyUsage::Verify],
+    )
+    .await?;
+    let data = b"what do ya want for nothing?";
+    let signature = crypto::sign(SignatureAlgorithm::Hmac, &key, data).await?;
+    assert_eq!(
+        hex(&signature),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+    assert!(crypto::verify(SignatureAlgorithm::Hmac, &key, &signature, data).await?);
+    assert!(!crypto::verify(SignatureAlgorithm::Hmac, &key, &signature, DATA).await?);
+    Ok(())
+}
+
+async fn ecdsa() -> Result<(), WorkerError> {
+    let (private, public) = key_pair(EC_P256, KeyAlgorithm::Ecdsa {
+        curve: NamedCurve::P256,
+    })
+    .await?;
+    let algorithm = SignatureAlgorithm::Ecdsa {
+        hash: HashAlgorithm::Sha256,
+    };
+    let signature = sign_and_verify(algorithm, &private, &public).await?;
+    assert_eq!(signature.len(), 64);
+    Ok(())
+}
+
+async fn ed25519() -> Result<(), WorkerError> {
+    let (private, public) = key_pair(ED25519, KeyAlgorithm::Ed25519).await?;
+    sign_and_verify(SignatureAlgorithm::Ed25519, &private, &public).await?;
+
+    // The public key also round-trips as raw bytes.
+    let KeyData::Raw(raw) = crypto::export_key(&public, KeyFormat::Raw).await? else {
+        panic!("The key was not exported as raw bytes");
+    };
+    assert_eq!(raw.len(), 32);
+    let public = crypto::import_key(KeyData::Raw(raw), KeyAlgorithm::Ed25519, false, &[
+        KeyUsage::Verify,
+    ])
+    .await?;
+    sign_and_verify(SignatureAlgorithm::Ed25519, &private, &public).await?;
+    Ok(())
+}
+
+async fn rsa_pss() -> Result<(), WorkerError> {
+    let (private, public) = key_pair(RSA_2048, KeyAlgorithm::RsaPss {
+        hash: HashAlgorithm::Sha256,
+    })
+    .await?;
+    let signature = sign_and_verify(
+        SignatureAlgorithm::RsaPss { salt_length: 32 },
+        &private,
+        &public,
+    )
+    .await?;
+    assert_eq!(signature.len(), 256);
+    Ok(())
+}
+
+async fn aes_gcm() -> Result<(), WorkerError> {
+    let mut secret = [0; 32];
+    crypto::get_random_values(&mut secret)?;
+    let key = crypto::import_key(
+        KeyData::Raw(secret.to_vec()),
+        KeyAlgorithm::AesGcm,
+        true,
+        &[KeyUsage::Encrypt, KeyUsage::Decrypt],
+    )
+    .await?;
+    assert_eq!(
+        crypto::export_key(&key, KeyFormat::Raw).await?,
+        KeyData::Raw(secret.to_vec())
+    );
+
+    let mut iv = [0; 12];
+    crypto::get_random_values(&mut iv)?;
+    let ciphertext = crypto::encrypt_aes_gcm(&key, &iv, DATA, Some(b"header")).await?;
+    // The ciphertext carries a 16-byte authentication tag.
+    assert_eq!(ciphertext.len(), DATA.len() + 16);
+    let plaintext = crypto::decrypt_aes_gcm(&key, &iv, &ciphertext, Some(b"header")).await?;
+    assert_eq!(plaintext, DATA);
+    assert!(
+        crypto::decrypt_aes_gcm(&key, &iv, &ciphertext, Some(b"other"))
+            .await
+            .is_err(),
+        "The ciphertext was decrypted with other additional data"
+    );
+    Ok(())
+}
+
+async fn pkcs8() -> Result<(), WorkerError> {
+    let algorithm = KeyAlgorithm::Ecdsa {
+        curve: NamedCurve::P256,
+    };
+    let (private, public) = key_pair(EC_P256, algorithm).await?;
+    let KeyData::Pkcs8(exported) = crypto::export_key(&private, KeyFormat::Pkcs8).await? else {
+        panic!("The key was not exported as PKCS #8");
+    };
+    let private = crypto::import_key(KeyData::Pkcs8(exported), algorithm, false, &[
+        KeyUsage::Sign,
+    ])
+    .await?;
+    let signature = SignatureAlgorithm::Ecdsa {
+        hash: HashAlgorithm::Sha256,
+    };
+    sign_and_verify(signature, &private, &public).await?;
+    Ok(())
+}
+
+async fn hkdf() -> Result<(), WorkerError> {
+    // RFC 5869, test case 1.
+    let key = crypto::import_key(KeyData::Raw(vec![0x0B; 22]), KeyAlgorithm::Hkdf, false, &[
+        KeyUsage::DeriveBits,
+    ])
+    .await?;
+    let algorithm = DeriveAlgorithm::Hkdf {
+        hash: HashAlgorithm::Sha256,
+        salt: (0x00..=0x0C).collect(),
+        info: (0xF0..=0xF9).collect(),
+    };
+    let okm = crypto::derive_bits(&algorithm, &key, 42 * 8).await?;
+    assert_eq!(
+        hex(&okm),
+        "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+    );
+    Ok(())
+}
+
+async fn pbkdf2() -> Result<(), WorkerError> {
+    // RFC 6070, the test cases with 1 and 2 iterations.
+    let key = crypto::import_key(
+        KeyData::Raw(b"password".to_vec()),
+        KeyAlgorithm::Pbkdf2,
+        false,
+        &[KeyUsage::DeriveBits],
+    )
+    .await?;
+    for (iterations, expected) in [
+        (1, "0c60c80f961f0e71f3a9b524af6012062fe037a6"),
+        (2, "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957"),
+    ] {
+        let algorithm = DeriveAlgorithm::Pbkdf2 {
+            hash: HashAlgorithm::Sha1,
+            salt: b"salt".to_vec(),
+            iterations,
+        };
+        let dk = crypto::derive_bits(&algorithm, &key, 20 * 8).await?;
+        assert_eq!(hex(&dk), expected);
+    }
+    Ok(())
+}
+
+fn timing_safe_equal() -> Result<(), WorkerError> {
+    assert!(crypto::timing_safe_equal(b"secret", b"secret")?);
+    assert!(!crypto::timing_safe_equal(b"secret", b"secreT")?);
+    assert!(!crypto::timing_safe_equal(b"secret", b"secret!")?);
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use std::time::{Duration, Instant};
 
 use betterworker::http::StatusCode;
+use retry::delay::Fixed;
 use serde::Deserialize;
 use util::*;
 
@@ -155,6 +156,90 @@ fn durable() {
     assert!(body.starts_with("[durable_object]"));
 }
 
+#[test]
+fn durable_rpc() {
+    let body = get("durable/rpc", |r| r).text().unwrap();
+    assert_eq!(body, "5");
+}
+
+#[test]
+fn durable_concurrent() {
+    let body = get("durable/concurrent", |r| r).text().unwrap();
+    assert_eq!(body, "3");
+}
+
+#[test]
+fn durable_lifecycle() {
+    let body = get("durable/lifecycle", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn durable_abort() {
+    let body = get("durable/abort", |r| r).text().unwrap();
+    assert_eq!(body, "Hello!");
+}
+
+#[test]
+fn durable_persist() {
+    let body = get("durable/persist", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn durable_retry() {
+    let body = get("durable/retry", |r| r).text().unwrap();
+    assert_eq!(body, "Hello!");
+}
+
+#[test]
+fn durable_primitives_lock() {
+    let body = get("durable/primitives/lock", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn durable_primitives_semaphore() {
+    let body = get("durable/primitives/semaphore", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn durable_primitives_counter() {
+    let body = get("durable/primitives/counter", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn durable_primitives_lease() {
+    let body = get("durable/primitives/lease", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn durable_retry_retryable() {
+    let body = get("durable/retry/retryable", |r| r).text().unwrap();
+    assert_eq!(body, "3");
+}
+
+#[test]
+fn durable_retry_overloaded() {
+    let body = get("durable/retry/overloaded", |r| r).text().unwrap();
+    assert_eq!(body, "1");
+}
+
+#[test]
+fn rpc_entrypoint() {
+    let body = get("rpc/entrypoint", |r| r).text().unwrap();
+    assert_eq!(body, "echo");
+}
+
+#[test]
+fn durable_placement() {
+    let body = get("durable/placement", |r| r).text().unwrap();
+    assert_eq!(body, "1");
+}
+
 #[test]
 fn durable_alarm() {
     let body = get("durable/alarm", |r| r).text().unwrap();
@@ -167,6 +252,69 @@ fn durable_alarm() {
     assert_eq!(body, "true");
 }
 
+#[derive(Deserialize)]
+struct FiredTimers {
+    fired: Vec<String>,
+    retry_count: Option<u32>,
+    is_retry: Option<bool>,
+}
+
+#[test]
+fn durable_timers() {
+    let name = post("durable/timers", |r| r).text().unwrap();
+
+    // The first attempt of the alarm fails, and the runtime retries it after a
+    // backoff of a few seconds.
+    let timers = retry::retry(Fixed::from_millis(500).take(30), || {
+        let timers: FiredTimers = get(&format!("durable/timers?name={name}"), |r| r)
+            .json()
+            .expect("Failed to get Json");
+        match timers.fired.len() {
+            2 => Ok(timers),
+            _ => Err("The timers did not fire"),
+        }
+    })
+    .unwrap();
+
+    assert_eq!(timers.fired, ["first", "second"]);
+    assert_eq!(timers.retry_count, Some(1));
+    assert_eq!(timers.is_retry, Some(true));
+}
+
+#[test]
+fn durable_transaction() {
+    let first: usize = get("durable/transaction", |r| r)
+        .text()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let second: usize = get("durable/transaction", |r| r)
+        .text()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(second, first + 1);
+}
+
+#[test]
+fn durable_transaction_rollback() {
+    let body = get("durable/transaction/rollback", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn durable_sql() {
+    let first: usize = get("durable/sql", |r| r).text().unwrap().parse().unwrap();
+    let second: usize = get("durable/sql", |r| r).text().unwrap().parse().unwrap();
+    assert_eq!(second, first + 1);
+}
+
+#[test]
+fn durable_bookmark() {
+    let body = get("durable/bookmark", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
 #[test]
 fn service_binding() {
     let body: String = get("service-binding", |r| r).text().unwrap();
@@ -214,3 +362,111 @@ fn r2_delete() {
     let body = delete("r2/delete", |r| r).text().unwrap();
     assert_eq!(body, "ok");
 }
+
+#[test]
+fn crypto_digest() {
+    let body = post("crypto/digest", |r| r.body("hello")).text().unwrap();
+    assert_eq!(
+        body,
+        "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    );
+}
+
+#[test]
+fn crypto_digest_stream() {
+    let body = post("crypto/digest-stream", |r| r.body("hello"))
+        .text()
+        .unwrap();
+    assert_eq!(
+        body,
+        "5 2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    );
+}
+
+#[test]
+fn assets() {
+    let res = get("spa/static/app.js", |r| r);
+    assert_eq!(
+        res.headers()["cache-control"],
+        "public, max-age=31536000, immutable"
+    );
+    assert_eq!(res.text().unwrap(), "console.log(\"app\");\n");
+
+    let body = get("spa/api", |r| r).text().unwrap();
+    assert_eq!(body, "api");
+
+    // Navigation requests to client-side routes get the entry point of the app.
+    let res = get("spa/todos/1", |r| r.header("accept", "text/html"));
+    assert_ne!(
+        res.headers()
+            .get("cache-control")
+            .map(|value| value.to_str().unwrap()),
+        Some("public, max-age=31536000, immutable")
+    );
+    assert!(res.text().unwrap().contains("<title>spa</title>"));
+
+    let status = reqwest::blocking::get("http://127.0.0.1:8787/spa/todos/1")
+        .unwrap()
+        .status();
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn crypto_hmac() {
+    let body = get("crypto/hmac", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn crypto_ecdsa() {
+    let body = get("crypto/ecdsa", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn crypto_ed25519() {
+    let body = get("crypto/ed25519", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn crypto_rsa_pss() {
+    let body = get("crypto/rsa-pss", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn crypto_aes_gcm() {
+    let body = get("crypto/aes-gcm", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn crypto_pkcs8() {
+    let body = get("crypto/pkcs8", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn crypto_hkdf() {
+    let body = get("crypto/hkdf", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn crypto_pbkdf2() {
+    let body = get("crypto/pbkdf2", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn crypto_timing_safe_equal() {
+    let body = get("crypto/timing-safe-equal", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
+
+#[test]
+fn jwt_verify() {
+    let body = get("jwt/verify", |r| r).text().unwrap();
+    assert_eq!(body, "ok");
+}
@@ -0,0 +1,24 @@
+use retry::delay::Fixed;
+
+use crate::util::{expect_wrangler, get};
+
+mod util;
+
+#[test]
+fn receive_trace_items() {
+    expect_wrangler();
+
+    let body = get("tail/ping", |r| r).text().unwrap();
+    assert_eq!(body, "pong");
+
+    // Trace items are delivered after the request completes.
+    retry::retry(Fixed::from_millis(500).take(5), || {
+        let urls: Vec<String> = get("tail", |r| r).json().expect("Failed to get Json");
+        if urls.iter().any(|url| url.ends_with("/tail/ping")) {
+            Ok(())
+        } else {
+            Err("The request was not traced")
+        }
+    })
+    .unwrap();
+}
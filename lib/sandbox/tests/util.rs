@@ -33,6 +33,7 @@ pub fn get(endpoint: &str, builder_fn: impl FnOnce(RequestBuilder) -> RequestBui
         .unwrap_or_else(|_| panic!("received invalid status code for {}", endpoint))
 }
 
+#[allow(dead_code)]
 pub fn post(endpoint: &str, builder_fn: impl FnOnce(RequestBuilder) -> RequestBuilder) -> Response {
     expect_wrangler();
 
@@ -35,3 +35,21 @@ fn websocket() {
         .expect("body was not boolean");
     assert!(got_close_event)
 }
+
+#[test]
+fn websocket_hibernation() {
+    util::expect_wrangler();
+
+    let (mut socket, _) =
+        connect("ws://127.0.0.1:8787/durable/websocket").expect("Can't connect");
+
+    socket.send(Message::Text("ping".into())).unwrap();
+    let msg = socket.read().and_then(|msg| msg.into_text()).unwrap();
+    assert_eq!(&msg, "pong");
+
+    for count in 1..=2 {
+        socket.send(Message::Text("hello".into())).unwrap();
+        let msg = socket.read().and_then(|msg| msg.into_text()).unwrap();
+        assert_eq!(msg, format!("{count} hello"));
+    }
+}
@@ -0,0 +1,37 @@
+use retry::delay::Fixed;
+use serde::Deserialize;
+
+use crate::util::{expect_wrangler, get, post};
+
+mod util;
+
+#[derive(Deserialize)]
+struct Status {
+    complete: bool,
+    output: Option<serde_json::Value>,
+    error: Option<serde_json::Value>,
+}
+
+#[test]
+fn run_workflow_instance() {
+    expect_wrangler();
+
+    let id = post("workflow", |r| r).text().unwrap();
+
+    // The instance sleeps for two seconds before it waits for its event.
+    let status = retry::retry(Fixed::from_millis(500).take(20), || {
+        let status: Status = get(&format!("workflow?id={id}"), |r| r)
+            .json()
+            .expect("Failed to get Json");
+        match status {
+            Status {
+                error: Some(error), ..
+            } => panic!("The instance failed: {error}"),
+            Status { complete: true, .. } => Ok(status),
+            _ => Err("The instance did not complete"),
+        }
+    })
+    .unwrap();
+
+    assert_eq!(status.output, Some(serde_json::json!(21)));
+}
@@ -5,6 +5,7 @@ mod headers;
 mod request;
 mod response;
 mod response_init;
+mod subtle_crypto;
 mod websocket;
 
 pub use abort_controller::*;
@@ -14,4 +15,5 @@ pub use headers::*;
 pub use request::*;
 pub use response::*;
 pub use response_init::*;
+pub use subtle_crypto::*;
 pub use websocket::*;
@@ -0,0 +1,29 @@
+use wasm_bindgen::prelude::*;
+
+mod glue {
+    use super::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(extends=js_sys::Object)]
+        pub type SubtleCrypto;
+
+        #[wasm_bindgen(method, catch, js_name=timingSafeEqual)]
+        pub fn timing_safe_equal(
+            this: &SubtleCrypto, a: &js_sys::Object, b: &js_sys::Object,
+        ) -> Result<bool, JsValue>;
+    }
+}
+
+pub trait SubtleCryptoExt {
+    /// Compares two buffers of equal length in constant time. This is a
+    /// non-standard extension of the Workers runtime.
+    fn timing_safe_equal(&self, a: &js_sys::Object, b: &js_sys::Object) -> Result<bool, JsValue>;
+}
+
+impl SubtleCryptoExt for web_sys::SubtleCrypto {
+    fn timing_safe_equal(&self, a: &js_sys::Object, b: &js_sys::Object) -> Result<bool, JsValue> {
+        self.unchecked_ref::<glue::SubtleCrypto>()
+            .timing_safe_equal(a, b)
+    }
+}
@@ -10,6 +10,12 @@ mod glue {
 
         #[wasm_bindgen(method, catch)]
         pub fn accept(this: &WebSocket) -> Result<(), JsValue>;
+
+        #[wasm_bindgen(method, catch, js_name=serializeAttachment)]
+        pub fn serialize_attachment(this: &WebSocket, value: JsValue) -> Result<(), JsValue>;
+
+        #[wasm_bindgen(method, catch, js_name=deserializeAttachment)]
+        pub fn deserialize_attachment(this: &WebSocket) -> Result<JsValue, JsValue>;
     }
 }
 
@@ -18,10 +24,31 @@ pub trait WebSocketExt {
     ///
     /// [CF Documentation](https://developers.cloudflare.com/workers/runtime-apis/websockets#accept)
     fn accept(&self) -> Result<(), JsValue>;
+
+    /// Keeps a copy of `value` associated with the WebSocket, which survives
+    /// the hibernation of a Durable Object.
+    ///
+    /// [CF Documentation](https://developers.cloudflare.com/durable-objects/api/websockets/#serializeattachment)
+    fn serialize_attachment(&self, value: JsValue) -> Result<(), JsValue>;
+
+    /// Retrieves the value passed to `serializeAttachment`, or `null`.
+    ///
+    /// [CF Documentation](https://developers.cloudflare.com/durable-objects/api/websockets/#deserializeattachment)
+    fn deserialize_attachment(&self) -> Result<JsValue, JsValue>;
 }
 
 impl WebSocketExt for web_sys::WebSocket {
     fn accept(&self) -> Result<(), JsValue> {
         self.unchecked_ref::<glue::WebSocket>().accept()
     }
+
+    fn serialize_attachment(&self, value: JsValue) -> Result<(), JsValue> {
+        self.unchecked_ref::<glue::WebSocket>()
+            .serialize_attachment(value)
+    }
+
+    fn deserialize_attachment(&self) -> Result<JsValue, JsValue> {
+        self.unchecked_ref::<glue::WebSocket>()
+            .deserialize_attachment()
+    }
 }
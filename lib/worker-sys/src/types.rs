@@ -1,8 +1,10 @@
 mod context;
 #[cfg(feature = "d1")]
 mod d1;
+mod digest_stream;
 mod durable_object;
 mod dynamic_dispatcher;
+mod email;
 mod env;
 mod fetcher;
 mod fixed_length_stream;
@@ -10,16 +12,21 @@ mod incoming_request_cf_properties;
 #[cfg(feature = "queue")]
 mod queue;
 mod r2;
+mod rate_limiter;
 mod schedule;
 mod socket;
 mod tls_client_auth;
 mod websocket_pair;
+mod websocket_request_response_pair;
+mod workflow;
 
 pub use context::*;
 #[cfg(feature = "d1")]
 pub use d1::*;
+pub use digest_stream::*;
 pub use durable_object::*;
 pub use dynamic_dispatcher::*;
+pub use email::*;
 pub use env::*;
 pub use fetcher::*;
 pub use fixed_length_stream::*;
@@ -27,7 +34,10 @@ pub use incoming_request_cf_properties::*;
 #[cfg(feature = "queue")]
 pub use queue::*;
 pub use r2::*;
+pub use rate_limiter::*;
 pub use schedule::*;
 pub use socket::*;
 pub use tls_client_auth::*;
 pub use websocket_pair::*;
+pub use websocket_request_response_pair::*;
+pub use workflow::*;
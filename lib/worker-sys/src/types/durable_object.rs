@@ -2,21 +2,32 @@ use wasm_bindgen::prelude::*;
 
 mod id;
 mod namespace;
+mod sql_storage;
 mod state;
 mod storage;
+mod sync_kv_storage;
 mod transaction;
 
 pub use id::*;
 pub use namespace::*;
+pub use sql_storage::*;
 pub use state::*;
 pub use storage::*;
+pub use sync_kv_storage::*;
 pub use transaction::*;
 
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(extends=js_sys::Object)]
+    #[derive(Debug, Clone)]
     pub type DurableObject;
 
+    #[wasm_bindgen(method, getter)]
+    pub fn id(this: &DurableObject) -> DurableObjectId;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn name(this: &DurableObject) -> Option<String>;
+
     #[wasm_bindgen(method, js_name=fetch)]
     pub fn fetch_with_request(this: &DurableObject, req: &web_sys::Request) -> js_sys::Promise;
 
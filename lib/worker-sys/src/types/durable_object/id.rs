@@ -3,8 +3,15 @@ use wasm_bindgen::prelude::*;
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(extends=js_sys::Object)]
+    #[derive(Debug, Clone)]
     pub type DurableObjectId;
 
     #[wasm_bindgen(method, js_name=toString)]
     pub fn to_string(this: &DurableObjectId) -> String;
+
+    #[wasm_bindgen(method)]
+    pub fn equals(this: &DurableObjectId, other: &DurableObjectId) -> bool;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn name(this: &DurableObjectId) -> Option<String>;
 }
@@ -5,6 +5,7 @@ use crate::types::durable_object::{DurableObject, DurableObjectId};
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(extends=js_sys::Object)]
+    #[derive(Debug, Clone)]
     pub type DurableObjectNamespace;
 
     #[wasm_bindgen(method, catch, js_name=idFromName)]
@@ -29,4 +30,23 @@ extern "C" {
     pub fn get(
         this: &DurableObjectNamespace, id: &DurableObjectId,
     ) -> Result<DurableObject, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=get)]
+    pub fn get_with_options(
+        this: &DurableObjectNamespace, id: &DurableObjectId, options: &JsValue,
+    ) -> Result<DurableObject, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=getByName)]
+    pub fn get_by_name(this: &DurableObjectNamespace, name: &str)
+        -> Result<DurableObject, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=getByName)]
+    pub fn get_by_name_with_options(
+        this: &DurableObjectNamespace, name: &str, options: &JsValue,
+    ) -> Result<DurableObject, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn jurisdiction(
+        this: &DurableObjectNamespace, jurisdiction: &str,
+    ) -> Result<DurableObjectNamespace, JsValue>;
 }
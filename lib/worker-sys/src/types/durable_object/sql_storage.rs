@@ -0,0 +1,36 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends=js_sys::Object)]
+    pub type SqlStorage;
+
+    #[wasm_bindgen(method, catch, variadic)]
+    pub fn exec(
+        this: &SqlStorage, query: &str, bindings: &js_sys::Array,
+    ) -> Result<SqlStorageCursor, JsValue>;
+
+    #[wasm_bindgen(method, getter, js_name=databaseSize)]
+    pub fn database_size(this: &SqlStorage) -> f64;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends=js_sys::Iterator)]
+    pub type SqlStorageCursor;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn one(this: &SqlStorageCursor) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn raw(this: &SqlStorageCursor) -> Result<js_sys::Iterator, JsValue>;
+
+    #[wasm_bindgen(method, getter, js_name=columnNames)]
+    pub fn column_names(this: &SqlStorageCursor) -> js_sys::Array;
+
+    #[wasm_bindgen(method, getter, js_name=rowsRead)]
+    pub fn rows_read(this: &SqlStorageCursor) -> f64;
+
+    #[wasm_bindgen(method, getter, js_name=rowsWritten)]
+    pub fn rows_written(this: &SqlStorageCursor) -> f64;
+}
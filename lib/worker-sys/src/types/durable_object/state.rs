@@ -1,6 +1,7 @@
 use wasm_bindgen::prelude::*;
 
 use crate::types::durable_object::{DurableObjectId, DurableObjectStorage};
+use crate::types::WebSocketRequestResponsePair;
 
 #[wasm_bindgen]
 extern "C" {
@@ -14,6 +15,47 @@ extern "C" {
     #[wasm_bindgen(method, getter)]
     pub fn storage(this: &DurableObjectState) -> DurableObjectStorage;
 
-    #[wasm_bindgen(method, js_name=blockConcurrencyWhile)]
-    pub fn block_concurrency_while(this: &DurableObjectState, promise: &js_sys::Promise);
+    #[wasm_bindgen(method, getter)]
+    pub fn exports(this: &DurableObjectState) -> Option<js_sys::Object>;
+
+    #[wasm_bindgen(method, catch, js_name=blockConcurrencyWhile)]
+    pub fn block_concurrency_while(
+        this: &DurableObjectState, callback: &js_sys::Function,
+    ) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, js_name=waitUntil)]
+    pub fn wait_until(this: &DurableObjectState, promise: &js_sys::Promise);
+
+    #[wasm_bindgen(method, catch)]
+    pub fn abort(this: &DurableObjectState, reason: &str) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=acceptWebSocket)]
+    pub fn accept_web_socket(
+        this: &DurableObjectState, ws: &web_sys::WebSocket, tags: &js_sys::Array,
+    ) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=getWebSockets)]
+    pub fn get_web_sockets(
+        this: &DurableObjectState, tag: Option<&str>,
+    ) -> Result<js_sys::Array, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=getTags)]
+    pub fn get_tags(
+        this: &DurableObjectState, ws: &web_sys::WebSocket,
+    ) -> Result<js_sys::Array, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=setWebSocketAutoResponse)]
+    pub fn set_web_socket_auto_response(
+        this: &DurableObjectState, pair: Option<&WebSocketRequestResponsePair>,
+    ) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name=getWebSocketAutoResponse)]
+    pub fn get_web_socket_auto_response(
+        this: &DurableObjectState,
+    ) -> Option<WebSocketRequestResponsePair>;
+
+    #[wasm_bindgen(method, catch, js_name=getWebSocketAutoResponseTimestamp)]
+    pub fn get_web_socket_auto_response_timestamp(
+        this: &DurableObjectState, ws: &web_sys::WebSocket,
+    ) -> Result<Option<js_sys::Date>, JsValue>;
 }
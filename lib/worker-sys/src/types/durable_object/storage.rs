@@ -1,38 +1,69 @@
 use wasm_bindgen::prelude::*;
 
-use crate::types::durable_object::DurableObjectTransaction;
+use crate::types::durable_object::{DurableObjectTransaction, SqlStorage, SyncKvStorage};
 
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(extends=js_sys::Object)]
+    #[derive(Debug, Clone)]
     pub type DurableObjectStorage;
 
     #[wasm_bindgen(method, catch)]
     pub fn get(this: &DurableObjectStorage, key: &str) -> Result<js_sys::Promise, JsValue>;
 
+    #[wasm_bindgen(method, catch, js_name=get)]
+    pub fn get_with_options(
+        this: &DurableObjectStorage, key: &str, options: js_sys::Object,
+    ) -> Result<js_sys::Promise, JsValue>;
+
     #[wasm_bindgen(method, catch, js_name=get)]
     pub fn get_multiple(
         this: &DurableObjectStorage, keys: Vec<JsValue>,
     ) -> Result<js_sys::Promise, JsValue>;
 
+    #[wasm_bindgen(method, catch, js_name=get)]
+    pub fn get_multiple_with_options(
+        this: &DurableObjectStorage, keys: Vec<JsValue>, options: js_sys::Object,
+    ) -> Result<js_sys::Promise, JsValue>;
+
     #[wasm_bindgen(method, catch)]
     pub fn put(
         this: &DurableObjectStorage, key: &str, value: JsValue,
     ) -> Result<js_sys::Promise, JsValue>;
 
+    #[wasm_bindgen(method, catch, js_name=put)]
+    pub fn put_with_options(
+        this: &DurableObjectStorage, key: &str, value: JsValue, options: js_sys::Object,
+    ) -> Result<js_sys::Promise, JsValue>;
+
     #[wasm_bindgen(method, catch, js_name=put)]
     pub fn put_multiple(
         this: &DurableObjectStorage, value: JsValue,
     ) -> Result<js_sys::Promise, JsValue>;
 
+    #[wasm_bindgen(method, catch, js_name=put)]
+    pub fn put_multiple_with_options(
+        this: &DurableObjectStorage, value: JsValue, options: js_sys::Object,
+    ) -> Result<js_sys::Promise, JsValue>;
+
     #[wasm_bindgen(method, catch)]
     pub fn delete(this: &DurableObjectStorage, key: &str) -> Result<js_sys::Promise, JsValue>;
 
+    #[wasm_bindgen(method, catch, js_name=delete)]
+    pub fn delete_with_options(
+        this: &DurableObjectStorage, key: &str, options: js_sys::Object,
+    ) -> Result<js_sys::Promise, JsValue>;
+
     #[wasm_bindgen(method, catch, js_name=delete)]
     pub fn delete_multiple(
         this: &DurableObjectStorage, keys: Vec<JsValue>,
     ) -> Result<js_sys::Promise, JsValue>;
 
+    #[wasm_bindgen(method, catch, js_name=delete)]
+    pub fn delete_multiple_with_options(
+        this: &DurableObjectStorage, keys: Vec<JsValue>, options: js_sys::Object,
+    ) -> Result<js_sys::Promise, JsValue>;
+
     #[wasm_bindgen(method, catch, js_name=deleteAll)]
     pub fn delete_all(this: &DurableObjectStorage) -> Result<js_sys::Promise, JsValue>;
 
@@ -46,7 +77,35 @@ extern "C" {
 
     #[wasm_bindgen(method, catch)]
     pub fn transaction(
-        this: &DurableObjectStorage, closure: &Closure<dyn FnMut(DurableObjectTransaction)>,
+        this: &DurableObjectStorage,
+        closure: &Closure<dyn FnMut(DurableObjectTransaction) -> js_sys::Promise>,
+    ) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn sync(this: &DurableObjectStorage) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=transactionSync)]
+    pub fn transaction_sync(
+        this: &DurableObjectStorage, closure: &js_sys::Function,
+    ) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn sql(this: &DurableObjectStorage) -> SqlStorage;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn kv(this: &DurableObjectStorage) -> SyncKvStorage;
+
+    #[wasm_bindgen(method, catch, js_name=getCurrentBookmark)]
+    pub fn get_current_bookmark(this: &DurableObjectStorage) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=getBookmarkForTime)]
+    pub fn get_bookmark_for_time(
+        this: &DurableObjectStorage, timestamp: &js_sys::Date,
+    ) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=onNextSessionRestoreBookmark)]
+    pub fn on_next_session_restore_bookmark(
+        this: &DurableObjectStorage, bookmark: &str,
     ) -> Result<js_sys::Promise, JsValue>;
 
     #[wasm_bindgen(method, catch, js_name=getAlarm)]
@@ -0,0 +1,20 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends=js_sys::Object)]
+    pub type SyncKvStorage;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn get(this: &SyncKvStorage, key: &str) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn put(this: &SyncKvStorage, key: &str, value: JsValue) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn delete(this: &SyncKvStorage, key: &str) -> Result<bool, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn list(this: &SyncKvStorage, options: js_sys::Object)
+        -> Result<js_sys::Iterator, JsValue>;
+}
@@ -0,0 +1,62 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "cloudflare:email")]
+extern "C" {
+    #[wasm_bindgen(extends=js_sys::Object)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type EmailMessage;
+
+    #[wasm_bindgen(constructor, catch)]
+    pub fn new(from: &str, to: &str, raw: &JsValue) -> Result<EmailMessage, JsValue>;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn from(this: &EmailMessage) -> String;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn to(this: &EmailMessage) -> String;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends=js_sys::Object)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type ForwardableEmailMessage;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn from(this: &ForwardableEmailMessage) -> String;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn to(this: &ForwardableEmailMessage) -> String;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn headers(this: &ForwardableEmailMessage) -> web_sys::Headers;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn raw(this: &ForwardableEmailMessage) -> web_sys::ReadableStream;
+
+    #[wasm_bindgen(method, getter, js_name=rawSize)]
+    pub fn raw_size(this: &ForwardableEmailMessage) -> f64;
+
+    #[wasm_bindgen(method, js_name=setReject)]
+    pub fn set_reject(this: &ForwardableEmailMessage, reason: &str);
+
+    #[wasm_bindgen(method, catch)]
+    pub fn forward(
+        this: &ForwardableEmailMessage, rcpt_to: &str, headers: Option<web_sys::Headers>,
+    ) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn reply(
+        this: &ForwardableEmailMessage, message: &EmailMessage,
+    ) -> Result<js_sys::Promise, JsValue>;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends=js_sys::Object)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type SendEmail;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn send(this: &SendEmail, message: &EmailMessage) -> Result<js_sys::Promise, JsValue>;
+}
@@ -24,4 +24,7 @@ extern "C" {
 
     #[wasm_bindgen(method)]
     pub fn send(this: &Queue, mesage: JsValue) -> js_sys::Promise;
+
+    #[wasm_bindgen(method, js_name=sendBatch)]
+    pub fn send_batch(this: &Queue, messages: js_sys::Array) -> js_sys::Promise;
 }
@@ -0,0 +1,76 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends=js_sys::Object)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type Workflow;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn create(this: &Workflow, options: &JsValue) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn get(this: &Workflow, id: &str) -> Result<js_sys::Promise, JsValue>;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends=js_sys::Object)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type WorkflowInstance;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn id(this: &WorkflowInstance) -> String;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn pause(this: &WorkflowInstance) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn resume(this: &WorkflowInstance) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn terminate(this: &WorkflowInstance) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn restart(this: &WorkflowInstance) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn status(this: &WorkflowInstance) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=sendEvent)]
+    pub fn send_event(
+        this: &WorkflowInstance, event: &js_sys::Object,
+    ) -> Result<js_sys::Promise, JsValue>;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends=js_sys::Object)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub type WorkflowStep;
+
+    #[wasm_bindgen(method, catch, js_name=do)]
+    pub fn do_(
+        this: &WorkflowStep, name: &str, callback: &js_sys::Function,
+    ) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=do)]
+    pub fn do_with_config(
+        this: &WorkflowStep, name: &str, config: &JsValue, callback: &js_sys::Function,
+    ) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn sleep(
+        this: &WorkflowStep, name: &str, duration: f64,
+    ) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=sleepUntil)]
+    pub fn sleep_until(
+        this: &WorkflowStep, name: &str, timestamp: &js_sys::Date,
+    ) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name=waitForEvent)]
+    pub fn wait_for_event(
+        this: &WorkflowStep, name: &str, options: &js_sys::Object,
+    ) -> Result<js_sys::Promise, JsValue>;
+}
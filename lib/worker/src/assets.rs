@@ -0,0 +1,309 @@
+//! Bindings for [Workers Static Assets](https://developers.cloudflare.com/workers/static-assets/).
+//!
+//! The assets binding is configured in your wrangler.toml file with the
+//! `binding` field of the `[assets]` table and is accessed with
+//! [`Env::assets`](crate::env::Env::assets). [`AssetServer`] serves assets
+//! in front of the rest of the Worker.
+//!
+//! ```ignore
+//! let server = AssetServer::new(env.assets("ASSETS")?)
+//!     .spa_fallback(PathAndQuery::from_static("/"))
+//!     .cache_control(
+//!         "/assets/**",
+//!         HeaderValue::from_static("public, max-age=31536000, immutable"),
+//!     );
+//!
+//! server.handle(req, |req| async move { api(req, env).await }).await
+//! ```
+
+use std::convert::TryFrom;
+use std::future::Future;
+
+use http::header::{self, HeaderMap, HeaderValue};
+use http::uri::PathAndQuery;
+use http::{Method, StatusCode};
+use js_sys::Object;
+use wasm_bindgen::JsValue;
+
+use crate::body::Body;
+use crate::error::WorkerError;
+use crate::fetcher::Fetcher;
+use crate::result::Result;
+
+/// A static assets binding.
+pub struct Assets(Fetcher);
+
+impl Assets {
+    /// Fetches the asset matching the request URL. Responds with
+    /// `404 Not Found` if there is none, or with `304 Not Modified` if the
+    /// request is conditional and the asset did not change.
+    pub async fn fetch(&self, req: http::Request<Body>) -> Result<http::Response<Body>> {
+        self.0.fetch(req).await
+    }
+
+    /// The binding as a plain [`Fetcher`].
+    pub fn as_fetcher(&self) -> &Fetcher {
+        &self.0
+    }
+}
+
+impl AsRef<JsValue> for Assets {
+    fn as_ref(&self) -> &JsValue {
+        self.0.as_ref()
+    }
+}
+
+impl TryFrom<Object> for Assets {
+    type Error = WorkerError;
+
+    fn try_from(obj: Object) -> Result<Self> {
+        Fetcher::try_from(obj).map(Self)
+    }
+}
+
+impl From<Assets> for JsValue {
+    fn from(assets: Assets) -> Self {
+        JsValue::from(assets.0)
+    }
+}
+
+/// Serves static assets, passing requests that do not match an asset on to
+/// the rest of the Worker.
+///
+/// Only `GET` and `HEAD` requests are looked up in the assets binding.
+pub struct AssetServer {
+    assets: Assets,
+    spa_fallback: Option<PathAndQuery>,
+    cache_control: Vec<(String, HeaderValue)>,
+}
+
+impl AssetServer {
+    /// Creates a new asset server.
+    pub fn new(assets: Assets) -> Self {
+        Self {
+            assets,
+            spa_fallback: None,
+            cache_control: Vec::new(),
+        }
+    }
+
+    /// Serves the asset at `path` for navigation requests that match neither
+    /// an asset nor a route of the Worker, so a single-page application can
+    /// handle client-side routes.
+    ///
+    /// With the default `html_handling` of the assets binding, `/` serves
+    /// `index.html` while `/index.html` redirects to `/`, so pass `/` to fall
+    /// back to the root `index.html`.
+    pub fn spa_fallback(mut self, path: PathAndQuery) -> Self {
+        self.spa_fallback = Some(path);
+        self
+    }
+
+    /// Sets the `Cache-Control` header of assets whose path matches `glob`.
+    ///
+    /// In the glob `*` matches within a single path segment, `**` matches any
+    /// number of segments and `?` matches a single character. The first
+    /// matching glob wins.
+    pub fn cache_control(mut self, glob: impl Into<String>, value: HeaderValue) -> Self {
+        self.cache_control.push((glob.into(), value));
+        self
+    }
+
+    /// Responds with the matching asset, or with `fallback` if there is none.
+    pub async fn handle<F, Fut>(
+        &self, req: http::Request<Body>, fallback: F,
+    ) -> Result<http::Response<Body>>
+    where
+        F: FnOnce(http::Request<Body>) -> Fut,
+        Fut: Future<Output = Result<http::Response<Body>>>, {
+        if req.method() != Method::GET && req.method() != Method::HEAD {
+            return fallback(req).await;
+        }
+
+        let (parts, body) = req.into_parts();
+        let res = self.fetch_asset(&parts, None).await?;
+        if res.status() != StatusCode::NOT_FOUND {
+            return Ok(res);
+        }
+
+        let res = fallback(http::Request::from_parts(parts.clone(), body)).await?;
+        match &self.spa_fallback {
+            Some(path)
+                if res.status() == StatusCode::NOT_FOUND && is_navigation(&parts.headers) =>
+            {
+                self.fetch_asset(&parts, Some(path)).await
+            },
+            _ => Ok(res),
+        }
+    }
+
+    /// Fetches the asset for a request, or the asset at `path` if given.
+    async fn fetch_asset(
+        &self, parts: &http::request::Parts, path: Option<&PathAndQuery>,
+    ) -> Result<http::Response<Body>> {
+        let mut uri = parts.uri.clone().into_parts();
+        if let Some(path) = path {
+            uri.path_and_query = Some(path.clone());
+        }
+        let uri = http::Uri::from_parts(uri)?;
+
+        let mut req = http::Request::new(Body::empty());
+        *req.method_mut() = parts.method.clone();
+        *req.uri_mut() = uri;
+        *req.headers_mut() = parts.headers.clone();
+
+        let mut res = self.assets.fetch(req).await?;
+        if !res.status().is_success() && res.status() != StatusCode::NOT_MODIFIED {
+            return Ok(res);
+        }
+
+        let asset_path = path.map_or(parts.uri.path(), PathAndQuery::path);
+        if let Some((_, value)) = self
+            .cache_control
+            .iter()
+            .find(|(glob, _)| glob_match(glob, asset_path))
+        {
+            res.headers_mut()
+                .insert(header::CACHE_CONTROL, value.clone());
+        }
+
+        if res.status() == StatusCode::OK && is_not_modified(&parts.headers, res.headers()) {
+            let mut not_modified = http::Response::new(Body::empty());
+            *not_modified.status_mut() = StatusCode::NOT_MODIFIED;
+            for name in [
+                header::CACHE_CONTROL,
+                header::CONTENT_LOCATION,
+                header::DATE,
+                header::ETAG,
+                header::EXPIRES,
+                header::LAST_MODIFIED,
+                header::VARY,
+            ] {
+                if let Some(value) = res.headers_mut().remove(&name) {
+                    not_modified.headers_mut().insert(name, value);
+                }
+            }
+            return Ok(not_modified);
+        }
+
+        Ok(res)
+    }
+}
+
+/// Whether a request was made by a browser navigating to a page.
+fn is_navigation(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .any(|value| value.contains("text/html"))
+}
+
+/// Evaluates the conditional headers of a request against the headers of the
+/// response, following the precedence of RFC 9110: `If-Modified-Since` is
+/// ignored if `If-None-Match` is present.
+fn is_not_modified(req: &HeaderMap, res: &HeaderMap) -> bool {
+    if let Some(if_none_match) = req.get(header::IF_NONE_MATCH) {
+        let (Ok(if_none_match), Some(Ok(etag))) = (
+            if_none_match.to_str(),
+            res.get(header::ETAG).map(HeaderValue::to_str),
+        ) else {
+            return false;
+        };
+
+        let etag = etag.trim_start_matches("W/");
+        return if_none_match
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag);
+    }
+
+    let (Some(Ok(if_modified_since)), Some(Ok(last_modified))) = (
+        req.get(header::IF_MODIFIED_SINCE).map(HeaderValue::to_str),
+        res.get(header::LAST_MODIFIED).map(HeaderValue::to_str),
+    ) else {
+        return false;
+    };
+
+    match (
+        chrono::DateTime::parse_from_rfc2822(if_modified_since),
+        chrono::DateTime::parse_from_rfc2822(last_modified),
+    ) {
+        (Ok(if_modified_since), Ok(last_modified)) => last_modified <= if_modified_since,
+        _ => false,
+    }
+}
+
+/// Matches a path against a glob where `*` matches within a path segment,
+/// `**` matches across segments and `?` matches a single character.
+fn glob_match(glob: &str, path: &str) -> bool {
+    fn matches(glob: &[u8], path: &[u8]) -> bool {
+        match glob {
+            [] => path.is_empty(),
+            [b'*', b'*', b'/', rest @ ..] => (0..=path.len())
+                .filter(|&i| i == 0 || path[i - 1] == b'/')
+                .any(|i| matches(rest, &path[i..])),
+            [b'*', b'*', rest @ ..] => (0..=path.len()).any(|i| matches(rest, &path[i..])),
+            [b'*', rest @ ..] => (0..=path.len())
+                .take_while(|&i| i == 0 || path[i - 1] != b'/')
+                .any(|i| matches(rest, &path[i..])),
+            [b'?', rest @ ..] => {
+                matches!(path.first(), Some(c) if *c != b'/') && matches(rest, &path[1..])
+            },
+            [c, rest @ ..] => path.first() == Some(c) && matches(rest, &path[1..]),
+        }
+    }
+
+    matches(glob.as_bytes(), path.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_globs() {
+        assert!(glob_match("/index.html", "/index.html"));
+        assert!(glob_match("/*.html", "/about.html"));
+        assert!(!glob_match("/*.html", "/blog/post.html"));
+        assert!(glob_match("/**/*.html", "/blog/post.html"));
+        assert!(glob_match("/**/*.html", "/index.html"));
+        assert!(glob_match("/assets/**", "/assets/js/app.js"));
+        assert!(!glob_match("/assets/**", "/static/app.js"));
+        assert!(glob_match("/img/?.png", "/img/a.png"));
+        assert!(!glob_match("/img/?.png", "/img/ab.png"));
+    }
+
+    #[test]
+    fn evaluates_conditional_headers() {
+        let mut res = HeaderMap::new();
+        res.insert(header::ETAG, HeaderValue::from_static("\"abc\""));
+        res.insert(
+            header::LAST_MODIFIED,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+
+        let mut req = HeaderMap::new();
+        req.insert(header::IF_NONE_MATCH, HeaderValue::from_static("W/\"abc\""));
+        assert!(is_not_modified(&req, &res));
+
+        req.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_static("\"xyz\", \"def\""),
+        );
+        req.insert(
+            header::IF_MODIFIED_SINCE,
+            HeaderValue::from_static("Thu, 22 Oct 2015 07:28:00 GMT"),
+        );
+        assert!(!is_not_modified(&req, &res));
+
+        req.remove(header::IF_NONE_MATCH);
+        assert!(is_not_modified(&req, &res));
+
+        req.insert(
+            header::IF_MODIFIED_SINCE,
+            HeaderValue::from_static("Tue, 20 Oct 2015 07:28:00 GMT"),
+        );
+        assert!(!is_not_modified(&req, &res));
+    }
+}
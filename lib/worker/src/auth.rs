@@ -0,0 +1,7 @@
+//! Authentication of incoming requests.
+//!
+//! [`jwt`] verifies JSON Web Tokens, such as the tokens
+//! [Cloudflare Access](https://developers.cloudflare.com/cloudflare-one/identity/authorization-cookie/validating-json/)
+//! adds to the requests it lets through.
+
+pub mod jwt;
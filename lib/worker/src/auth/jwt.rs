@@ -0,0 +1,684 @@
+//! Verification of JSON Web Tokens signed with `RS256`, `ES256` or `EdDSA`
+//! against the keys of a JSON Web Key Set.
+//!
+//! Signatures are verified with the [Web Crypto API](crate::crypto). The key
+//! set is cached in the isolate and in the default [`Cache`], so it is only
+//! fetched again once it expires or a token is signed with an unknown key.
+//!
+//! ```ignore
+//! #[derive(Deserialize)]
+//! struct Identity {
+//!     email: String,
+//! }
+//!
+//! let verifier = JwtVerifier::cloudflare_access("myteam.cloudflareaccess.com", env.var("ACCESS_AUD")?.to_string());
+//!
+//! verifier
+//!     .handle(req, |_req, claims: Claims<Identity>| async move {
+//!         Ok(http::Response::new(format!("Hello, {}", claims.custom.email).into()))
+//!     })
+//!     .await
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use http::header::{self, HeaderName, HeaderValue};
+use http::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::body::Body;
+use crate::cache::Cache;
+use crate::crypto::{self, HashAlgorithm, KeyAlgorithm, KeyData, KeyUsage, NamedCurve};
+use crate::date::Date;
+use crate::error::WorkerError;
+use crate::fetch::fetch;
+use crate::result::Result;
+
+/// The header Cloudflare Access adds the token of the authenticated user to.
+pub const CF_ACCESS_JWT_ASSERTION: HeaderName = HeaderName::from_static("cf-access-jwt-assertion");
+
+/// The minimum time between two fetches of a key set caused by tokens with an
+/// unknown key ID, so forged tokens cannot make every request fetch the keys.
+const MIN_REFRESH_INTERVAL: u64 = 60;
+
+/// The header a key set is stored in the cache with, holding the time it was
+/// fetched at in seconds since the Unix epoch.
+const FETCHED_AT: HeaderName = HeaderName::from_static("x-betterworker-fetched-at");
+
+thread_local! {
+    /// The key sets fetched by this isolate, by URL.
+    static KEY_SETS: RefCell<HashMap<String, CachedKeySet>> = RefCell::new(HashMap::new());
+}
+
+#[derive(Clone)]
+struct CachedKeySet {
+    keys: Vec<serde_json::Value>,
+    fetched_at: u64,
+    expires_at: u64,
+}
+
+/// Why a token was rejected.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JwtError {
+    #[error("no token in request")]
+    Missing,
+
+    #[error("malformed token: {0}")]
+    Malformed(String),
+
+    #[error("unsupported token algorithm `{0}`")]
+    UnsupportedAlgorithm(String),
+
+    #[error("no key matches the token key ID {0:?}")]
+    UnknownKey(Option<String>),
+
+    #[error("invalid token signature")]
+    InvalidSignature,
+
+    #[error("token is missing the `{0}` claim")]
+    MissingClaim(&'static str),
+
+    #[error("token has expired")]
+    Expired,
+
+    #[error("token is not valid yet")]
+    NotYetValid,
+
+    #[error("token audience is not accepted")]
+    InvalidAudience,
+
+    #[error("token issuer is not accepted")]
+    InvalidIssuer,
+
+    #[error("failed to fetch the key set: {0}")]
+    KeySet(String),
+}
+
+/// A signature algorithm of a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// RSASSA-PKCS1-v1_5 with SHA-256, used by Cloudflare Access.
+    Rs256,
+    /// ECDSA with the P-256 curve and SHA-256.
+    Es256,
+    /// Ed25519.
+    EdDsa,
+}
+
+impl Algorithm {
+    /// The name of the algorithm in the `alg` header of a token.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rs256 => "RS256",
+            Self::Es256 => "ES256",
+            Self::EdDsa => "EdDSA",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [Self::Rs256, Self::Es256, Self::EdDsa]
+            .into_iter()
+            .find(|alg| alg.name() == name)
+    }
+
+    /// The `kty` of JSON Web Keys for the algorithm.
+    fn key_type(&self) -> &'static str {
+        match self {
+            Self::Rs256 => "RSA",
+            Self::Es256 => "EC",
+            Self::EdDsa => "OKP",
+        }
+    }
+
+    fn key_algorithm(&self) -> KeyAlgorithm {
+        match self {
+            Self::Rs256 => KeyAlgorithm::RsaPkcs1 {
+                hash: HashAlgorithm::Sha256,
+            },
+            Self::Es256 => KeyAlgorithm::Ecdsa {
+                curve: NamedCurve::P256,
+            },
+            Self::EdDsa => KeyAlgorithm::Ed25519,
+        }
+    }
+
+    fn signature_algorithm(&self) -> crypto::SignatureAlgorithm {
+        match self {
+            Self::Rs256 => crypto::SignatureAlgorithm::RsaPkcs1,
+            Self::Es256 => crypto::SignatureAlgorithm::Ecdsa {
+                hash: HashAlgorithm::Sha256,
+            },
+            Self::EdDsa => crypto::SignatureAlgorithm::Ed25519,
+        }
+    }
+}
+
+/// The `aud` claim, which is either a single audience or a list.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Audience {
+    #[default]
+    None,
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Audience {
+    /// Whether `audience` is one of the audiences.
+    pub fn contains(&self, audience: &str) -> bool {
+        match self {
+            Self::None => false,
+            Self::One(one) => one == audience,
+            Self::Many(many) => many.iter().any(|aud| aud == audience),
+        }
+    }
+}
+
+/// The claims of a verified token: the registered claims, plus the remaining
+/// claims deserialized into `C`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Claims<C = serde_json::Map<String, serde_json::Value>> {
+    /// The issuer.
+    pub iss: Option<String>,
+    /// The subject, the user ID for Cloudflare Access.
+    pub sub: Option<String>,
+    #[serde(default)]
+    pub aud: Audience,
+    /// The expiration time in seconds since the Unix epoch.
+    pub exp: Option<u64>,
+    /// The time before which the token is not valid, in seconds since the Unix
+    /// epoch.
+    pub nbf: Option<u64>,
+    /// The time the token was issued, in seconds since the Unix epoch.
+    pub iat: Option<u64>,
+    #[serde(flatten)]
+    pub custom: C,
+}
+
+/// The identity claims of a Cloudflare Access token.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AccessIdentity {
+    /// The email of the user, absent for service tokens.
+    pub email: Option<String>,
+    /// The client ID of the service token, absent for users.
+    pub common_name: Option<String>,
+    /// The country the user authenticated from.
+    pub country: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenHeader {
+    alg: String,
+    kid: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct KeySet {
+    keys: Vec<serde_json::Value>,
+}
+
+/// Verifies tokens against the keys published at a JSON Web Key Set URL.
+#[derive(Debug, Clone)]
+pub struct JwtVerifier {
+    jwks_url: String,
+    header: HeaderName,
+    audiences: Vec<String>,
+    issuer: Option<String>,
+    algorithms: Vec<Algorithm>,
+    leeway: Duration,
+    jwks_ttl: Duration,
+}
+
+impl JwtVerifier {
+    /// Creates a verifier for tokens signed with the keys at `jwks_url`.
+    ///
+    /// No audience or issuer is checked until they are configured with
+    /// [`audience`](Self::audience) and [`issuer`](Self::issuer).
+    pub fn new(jwks_url: impl Into<String>) -> Self {
+        Self {
+            jwks_url: jwks_url.into(),
+            header: CF_ACCESS_JWT_ASSERTION,
+            audiences: Vec::new(),
+            issuer: None,
+            algorithms: vec![Algorithm::Rs256, Algorithm::Es256, Algorithm::EdDsa],
+            leeway: Duration::from_secs(60),
+            jwks_ttl: Duration::from_secs(60 * 60),
+        }
+    }
+
+    /// Creates a verifier for the tokens of a Cloudflare Access team, such as
+    /// `myteam.cloudflareaccess.com`, issued for the application with the
+    /// audience tag `audience`.
+    pub fn cloudflare_access(team_domain: &str, audience: impl Into<String>) -> Self {
+        let team_domain = team_domain
+            .trim_start_matches("https://")
+            .trim_end_matches('/');
+
+        Self::new(format!("https://{team_domain}/cdn-cgi/access/certs"))
+            .issuer(format!("https://{team_domain}"))
+            .audience(audience)
+            .algorithms(&[Algorithm::Rs256])
+    }
+
+    /// Accepts tokens for `audience`. Once an audience is added, tokens must
+    /// be issued for one of the accepted audiences.
+    pub fn audience(mut self, audience: impl Into<String>) -> Self {
+        self.audiences.push(audience.into());
+        self
+    }
+
+    /// Only accepts tokens issued by `issuer`.
+    pub fn issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// The signature algorithms tokens may use. Defaults to all supported
+    /// algorithms.
+    pub fn algorithms(mut self, algorithms: &[Algorithm]) -> Self {
+        self.algorithms = algorithms.to_vec();
+        self
+    }
+
+    /// The clock skew tolerated when checking `exp` and `nbf`. Defaults to
+    /// 60 seconds.
+    pub fn leeway(mut self, leeway: Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// How long the key set is cached. Defaults to one hour.
+    pub fn jwks_ttl(mut self, ttl: Duration) -> Self {
+        self.jwks_ttl = ttl;
+        self
+    }
+
+    /// The request header the token is read from. Defaults to
+    /// [`CF_ACCESS_JWT_ASSERTION`].
+    pub fn header(mut self, header: HeaderName) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Verifies a token and returns its claims.
+    pub async fn verify<C: DeserializeOwned>(&self, token: &str) -> Result<Claims<C>> {
+        let (header, payload, signature) = split_token(token)?;
+        let algorithm = Algorithm::from_name(&header.alg)
+            .filter(|alg| self.algorithms.contains(alg))
+            .ok_or_else(|| JwtError::UnsupportedAlgorithm(header.alg.clone()))?;
+
+        let keys = self.keys(header.kid.as_deref()).await?;
+        let candidates = keys
+            .iter()
+            .filter(|key| is_candidate(key, algorithm, header.kid.as_deref()))
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            return Err(JwtError::UnknownKey(header.kid).into());
+        }
+
+        let signed = &token[..token.rfind('.').unwrap_or_default()];
+        let mut valid = false;
+        for jwk in candidates {
+            // A key the runtime can't import can't have signed the token, but
+            // a later candidate still might have.
+            let Ok(key) = crypto::import_key(
+                KeyData::Jwk(jwk.clone()),
+                algorithm.key_algorithm(),
+                false,
+                &[KeyUsage::Verify],
+            )
+            .await
+            else {
+                continue;
+            };
+            if crypto::verify(
+                algorithm.signature_algorithm(),
+                &key,
+                &signature,
+                signed.as_bytes(),
+            )
+            .await?
+            {
+                valid = true;
+                break;
+            }
+        }
+        if !valid {
+            return Err(JwtError::InvalidSignature.into());
+        }
+
+        let claims: Claims<C> = serde_json::from_slice(&payload)
+            .map_err(|err| JwtError::Malformed(format!("invalid claims: {err}")))?;
+        self.validate(&claims, Date::now().as_millis() / 1000)?;
+        Ok(claims)
+    }
+
+    /// Reads the token from the configured header, or from an
+    /// `Authorization: Bearer` header, and verifies it.
+    pub async fn verify_request<C: DeserializeOwned, B>(
+        &self, req: &http::Request<B>,
+    ) -> Result<Claims<C>> {
+        let token = self.token(req).ok_or(JwtError::Missing)?;
+        self.verify(token).await
+    }
+
+    /// Runs `handler` with the claims of the request token. Responds with
+    /// `401 Unauthorized` if the request carries no token and with
+    /// `403 Forbidden` if the token is rejected.
+    pub async fn handle<C, F, Fut>(
+        &self, req: http::Request<Body>, handler: F,
+    ) -> Result<http::Response<Body>>
+    where
+        C: DeserializeOwned,
+        F: FnOnce(http::Request<Body>, Claims<C>) -> Fut,
+        Fut: Future<Output = Result<http::Response<Body>>>, {
+        let status = match self.verify_request(&req).await {
+            Ok(claims) => return handler(req, claims).await,
+            Err(WorkerError::JwtError(JwtError::Missing)) => StatusCode::UNAUTHORIZED,
+            Err(err @ WorkerError::JwtError(JwtError::KeySet(_))) => return Err(err),
+            Err(WorkerError::JwtError(_)) => StatusCode::FORBIDDEN,
+            Err(err) => return Err(err),
+        };
+
+        let res = http::Response::builder()
+            .status(status)
+            .body(status.canonical_reason().unwrap_or_default().into())
+            .unwrap();
+        Ok(res)
+    }
+
+    /// The token of a request, if it carries one.
+    pub fn token<'r, B>(&self, req: &'r http::Request<B>) -> Option<&'r str> {
+        if let Some(token) = req.headers().get(&self.header) {
+            return token.to_str().ok();
+        }
+
+        req.headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+    }
+
+    /// Checks the registered claims of a token at `now`, in seconds since the
+    /// Unix epoch.
+    fn validate<C>(&self, claims: &Claims<C>, now: u64) -> std::result::Result<(), JwtError> {
+        let leeway = self.leeway.as_secs();
+
+        let exp = claims.exp.ok_or(JwtError::MissingClaim("exp"))?;
+        if now > exp.saturating_add(leeway) {
+            return Err(JwtError::Expired);
+        }
+        if matches!(claims.nbf, Some(nbf) if now.saturating_add(leeway) < nbf) {
+            return Err(JwtError::NotYetValid);
+        }
+
+        if !self.audiences.is_empty() && !self.audiences.iter().any(|aud| claims.aud.contains(aud))
+        {
+            return Err(JwtError::InvalidAudience);
+        }
+
+        match &self.issuer {
+            Some(issuer) if claims.iss.as_ref() != Some(issuer) => Err(JwtError::InvalidIssuer),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the key set, from the isolate, the cache or the network. A
+    /// key set without the key `kid` is refreshed from the network at most
+    /// every [`MIN_REFRESH_INTERVAL`] seconds.
+    async fn keys(&self, kid: Option<&str>) -> Result<Vec<serde_json::Value>> {
+        let now = Date::now().as_millis() / 1000;
+        let cached = KEY_SETS.with(|sets| sets.borrow().get(&self.jwks_url).cloned());
+
+        let cached = match cached {
+            Some(cached) if cached.expires_at > now => Some(cached),
+            _ => self.load_cached(now).await?,
+        };
+
+        let refresh = match &cached {
+            None => true,
+            Some(cached) => {
+                !has_kid(&cached.keys, kid) && now >= cached.fetched_at + MIN_REFRESH_INTERVAL
+            },
+        };
+        if !refresh {
+            return Ok(cached.map(|cached| cached.keys).unwrap_or_default());
+        }
+
+        let fetched = self.fetch_keys(now).await?;
+        KEY_SETS.with(|sets| {
+            sets.borrow_mut()
+                .insert(self.jwks_url.clone(), fetched.clone())
+        });
+        Ok(fetched.keys)
+    }
+
+    /// Loads the key set from the default cache into the isolate.
+    async fn load_cached(&self, now: u64) -> Result<Option<CachedKeySet>> {
+        let Some(res) = Cache::default().get(&self.jwks_url, false).await? else {
+            return Ok(None);
+        };
+
+        // A key set cached without its fetch time, or by a verifier with a
+        // longer TTL, is fetched again.
+        let fetched_at = res
+            .headers()
+            .get(&FETCHED_AT)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        let cached = match fetched_at {
+            Some(fetched_at) if fetched_at + self.jwks_ttl.as_secs() > now => {
+                let key_set: KeySet = res.into_body().json().await?;
+                self.key_set(key_set.keys, fetched_at)
+            },
+            _ => return Ok(None),
+        };
+        KEY_SETS.with(|sets| {
+            sets.borrow_mut()
+                .insert(self.jwks_url.clone(), cached.clone())
+        });
+        Ok(Some(cached))
+    }
+
+    /// Fetches the key set and stores it in the default cache.
+    async fn fetch_keys(&self, now: u64) -> Result<CachedKeySet> {
+        let req = http::Request::get(&self.jwks_url)
+            .body(Body::empty())
+            .map_err(|err| JwtError::KeySet(err.to_string()))?;
+        let res = fetch(req).await?;
+        if !res.status().is_success() {
+            return Err(
+                JwtError::KeySet(format!("{} responded {}", self.jwks_url, res.status())).into(),
+            );
+        }
+
+        let body = res.into_body().bytes().await?;
+        let key_set: KeySet = serde_json::from_slice(&body)
+            .map_err(|err| JwtError::KeySet(format!("invalid key set: {err}")))?;
+
+        let mut cached = http::Response::new(Body::from(body));
+        cached.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_str(&format!("max-age={}", self.jwks_ttl.as_secs())).unwrap(),
+        );
+        cached.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        cached
+            .headers_mut()
+            .insert(FETCHED_AT, HeaderValue::from(now));
+        Cache::default().put(&self.jwks_url, cached).await?;
+
+        Ok(self.key_set(key_set.keys, now))
+    }
+
+    fn key_set(&self, keys: Vec<serde_json::Value>, fetched_at: u64) -> CachedKeySet {
+        CachedKeySet {
+            keys,
+            fetched_at,
+            expires_at: fetched_at + self.jwks_ttl.as_secs(),
+        }
+    }
+}
+
+/// Splits a token into its decoded header, payload and signature.
+fn split_token(token: &str) -> std::result::Result<(TokenHeader, Vec<u8>, Vec<u8>), JwtError> {
+    let mut parts = token.split('.');
+    let (Some(header), Some(payload), Some(signature), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(JwtError::Malformed("expected three segments".into()));
+    };
+
+    let decode = |segment: &str, name: &str| {
+        URL_SAFE_NO_PAD
+            .decode(segment)
+            .map_err(|err| JwtError::Malformed(format!("invalid {name}: {err}")))
+    };
+    let header = serde_json::from_slice(&decode(header, "header")?)
+        .map_err(|err| JwtError::Malformed(format!("invalid header: {err}")))?;
+
+    Ok((
+        header,
+        decode(payload, "payload")?,
+        decode(signature, "signature")?,
+    ))
+}
+
+/// Whether the JSON Web Key `jwk` may have signed a token with `algorithm`
+/// and the key ID `kid`.
+fn is_candidate(jwk: &serde_json::Value, algorithm: Algorithm, kid: Option<&str>) -> bool {
+    let field = |name: &str| jwk.get(name).and_then(serde_json::Value::as_str);
+
+    field("kty") == Some(algorithm.key_type())
+        && field("alg").is_none_or(|alg| alg == algorithm.name())
+        && field("use").is_none_or(|usage| usage == "sig")
+        && kid.is_none_or(|kid| field("kid") == Some(kid))
+}
+
+fn has_kid(keys: &[serde_json::Value], kid: Option<&str>) -> bool {
+    match kid {
+        Some(kid) => keys
+            .iter()
+            .any(|key| key.get("kid").and_then(serde_json::Value::as_str) == Some(kid)),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn claims(value: serde_json::Value) -> Claims {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn splits_tokens() {
+        let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"RS256","kid":"a","typ":"JWT"}"#);
+        let payload = URL_SAFE_NO_PAD.encode(r#"{"sub":"user"}"#);
+        let token = format!("{header}.{payload}.c2ln");
+
+        let (header, payload, signature) = split_token(&token).unwrap();
+        assert_eq!(header.alg, "RS256");
+        assert_eq!(header.kid.as_deref(), Some("a"));
+        assert_eq!(payload, br#"{"sub":"user"}"#);
+        assert_eq!(signature, b"sig");
+
+        assert!(matches!(split_token("a.b"), Err(JwtError::Malformed(_))));
+        assert!(matches!(
+            split_token(&format!("{token}.d")),
+            Err(JwtError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn validates_claims() {
+        let verifier = JwtVerifier::cloudflare_access("https://team.cloudflareaccess.com/", "app");
+        assert_eq!(
+            verifier.jwks_url,
+            "https://team.cloudflareaccess.com/cdn-cgi/access/certs"
+        );
+
+        let valid = json!({
+            "iss": "https://team.cloudflareaccess.com",
+            "aud": ["app"],
+            "exp": 1000,
+            "nbf": 900,
+            "email": "user@example.com",
+        });
+        assert_eq!(verifier.validate(&claims(valid.clone()), 950), Ok(()));
+        assert_eq!(verifier.validate(&claims(valid.clone()), 1050), Ok(()));
+        assert_eq!(verifier.validate(&claims(valid.clone()), 850), Ok(()));
+        assert_eq!(
+            verifier.validate(&claims(valid.clone()), 1061),
+            Err(JwtError::Expired)
+        );
+        assert_eq!(
+            verifier.validate(&claims(valid.clone()), 839),
+            Err(JwtError::NotYetValid)
+        );
+
+        let mut other_aud = valid.clone();
+        other_aud["aud"] = json!("other");
+        assert_eq!(
+            verifier.validate(&claims(other_aud), 950),
+            Err(JwtError::InvalidAudience)
+        );
+
+        let mut other_iss = valid.clone();
+        other_iss["iss"] = json!("https://evil.example.com");
+        assert_eq!(
+            verifier.validate(&claims(other_iss), 950),
+            Err(JwtError::InvalidIssuer)
+        );
+
+        let mut no_exp = valid.clone();
+        no_exp.as_object_mut().unwrap().remove("exp");
+        assert_eq!(
+            verifier.validate(&claims(no_exp), 950),
+            Err(JwtError::MissingClaim("exp"))
+        );
+
+        let identity: Claims<AccessIdentity> = serde_json::from_value(valid).unwrap();
+        assert_eq!(identity.custom.email.as_deref(), Some("user@example.com"));
+    }
+
+    #[test]
+    fn selects_keys_and_tokens() {
+        let rsa = json!({ "kty": "RSA", "kid": "a", "alg": "RS256", "use": "sig" });
+        let ec = json!({ "kty": "EC", "kid": "b", "crv": "P-256" });
+        assert!(is_candidate(&rsa, Algorithm::Rs256, Some("a")));
+        assert!(is_candidate(&rsa, Algorithm::Rs256, None));
+        assert!(!is_candidate(&rsa, Algorithm::Rs256, Some("b")));
+        assert!(!is_candidate(&rsa, Algorithm::Es256, Some("a")));
+        assert!(is_candidate(&ec, Algorithm::Es256, Some("b")));
+        assert!(has_kid(&[rsa, ec], Some("b")));
+
+        let verifier = JwtVerifier::new("https://example.com/jwks");
+        let req = http::Request::get("/")
+            .header(header::AUTHORIZATION, "Bearer token")
+            .body(())
+            .unwrap();
+        assert_eq!(verifier.token(&req), Some("token"));
+
+        let req = http::Request::get("/")
+            .header(CF_ACCESS_JWT_ASSERTION, "access")
+            .header(header::AUTHORIZATION, "Bearer token")
+            .body(())
+            .unwrap();
+        assert_eq!(verifier.token(&req), Some("access"));
+    }
+}
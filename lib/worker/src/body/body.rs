@@ -219,6 +219,12 @@ impl From<web_sys::Response> for Body {
     }
 }
 
+impl From<web_sys::ReadableStream> for Body {
+    fn from(stream: web_sys::ReadableStream) -> Self {
+        Self::new(WasmStreamBody::new(stream))
+    }
+}
+
 macro_rules! body_from_impl {
     ($ty:ty) => {
         impl From<$ty> for Body {
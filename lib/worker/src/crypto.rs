@@ -0,0 +1,702 @@
+//! Bindings for the [Web Crypto API](https://developers.cloudflare.com/workers/runtime-apis/web-crypto/),
+//! which runs cryptographic operations natively in the runtime instead of
+//! compiling them into the Worker.
+//!
+//! ```ignore
+//! let key = crypto::import_key(
+//!     KeyData::Raw(secret.to_vec()),
+//!     KeyAlgorithm::Hmac { hash: HashAlgorithm::Sha256 },
+//!     false,
+//!     &[KeyUsage::Sign, KeyUsage::Verify],
+//! )
+//! .await?;
+//!
+//! let signature = crypto::sign(SignatureAlgorithm::Hmac, &key, body).await?;
+//! assert!(crypto::verify(SignatureAlgorithm::Hmac, &key, &signature, body).await?);
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use betterworker_sys::ext::SubtleCryptoExt;
+use betterworker_sys::DigestStream as DigestStreamSys;
+use bytes::Bytes;
+use futures_util::FutureExt;
+use http_body::Frame;
+use js_sys::{Array, JsString, Object as JsObject, Uint8Array};
+use send_wrapper::SendWrapper;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::WritableStreamDefaultWriter;
+
+use crate::body::{Body, HttpBody};
+use crate::error::WorkerError;
+use crate::futures::future_from_promise;
+use crate::r2::js_object;
+use crate::result::Result;
+use crate::rpc;
+
+/// The maximum number of bytes [get_random_values] fills in one call.
+const MAX_RANDOM_VALUES: usize = 65536;
+
+fn crypto() -> Result<web_sys::Crypto> {
+    let global: web_sys::WorkerGlobalScope = js_sys::global().unchecked_into();
+    global.crypto().map_err(WorkerError::from_js_err)
+}
+
+fn subtle() -> Result<web_sys::SubtleCrypto> {
+    Ok(crypto()?.subtle())
+}
+
+fn to_vec(buffer: JsValue) -> Vec<u8> {
+    Uint8Array::new(&buffer).to_vec()
+}
+
+/// A hash function for [digest], [digest_body] and [DigestStream].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+    /// MD5 is not part of the Web Crypto standard and can't be used with keys,
+    /// which take a [HashAlgorithm]. It is not collision resistant.
+    Md5,
+}
+
+impl DigestAlgorithm {
+    /// The name of the algorithm in the Web Crypto API.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+            Self::Sha384 => "SHA-384",
+            Self::Sha512 => "SHA-512",
+            Self::Md5 => "MD5",
+        }
+    }
+}
+
+impl From<HashAlgorithm> for DigestAlgorithm {
+    fn from(hash: HashAlgorithm) -> Self {
+        match hash {
+            HashAlgorithm::Sha1 => Self::Sha1,
+            HashAlgorithm::Sha256 => Self::Sha256,
+            HashAlgorithm::Sha384 => Self::Sha384,
+            HashAlgorithm::Sha512 => Self::Sha512,
+        }
+    }
+}
+
+/// A hash function for keys, signatures and derivation, which the Web Crypto
+/// API only supports from the SHA family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    /// The name of the algorithm in the Web Crypto API.
+    pub fn name(&self) -> &'static str {
+        DigestAlgorithm::from(*self).name()
+    }
+}
+
+/// An elliptic curve for ECDSA keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamedCurve {
+    P256,
+    P384,
+    P521,
+}
+
+impl NamedCurve {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::P256 => "P-256",
+            Self::P384 => "P-384",
+            Self::P521 => "P-521",
+        }
+    }
+}
+
+/// The algorithm a key is imported for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAlgorithm {
+    Hmac { hash: HashAlgorithm },
+    Ecdsa { curve: NamedCurve },
+    Ed25519,
+    RsaPss { hash: HashAlgorithm },
+    RsaPkcs1 { hash: HashAlgorithm },
+    AesGcm,
+    Hkdf,
+    Pbkdf2,
+}
+
+impl KeyAlgorithm {
+    fn to_js(self) -> JsObject {
+        match self {
+            Self::Hmac { hash } => js_object! { "name" => "HMAC", "hash" => hash.name() },
+            Self::Ecdsa { curve } => js_object! { "name" => "ECDSA", "namedCurve" => curve.name() },
+            Self::Ed25519 => js_object! { "name" => "Ed25519" },
+            Self::RsaPss { hash } => js_object! { "name" => "RSA-PSS", "hash" => hash.name() },
+            Self::RsaPkcs1 { hash } => {
+                js_object! { "name" => "RSASSA-PKCS1-v1_5", "hash" => hash.name() }
+            },
+            Self::AesGcm => js_object! { "name" => "AES-GCM" },
+            Self::Hkdf => js_object! { "name" => "HKDF" },
+            Self::Pbkdf2 => js_object! { "name" => "PBKDF2" },
+        }
+    }
+}
+
+/// An operation a key may be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyUsage {
+    Encrypt,
+    Decrypt,
+    Sign,
+    Verify,
+    DeriveKey,
+    DeriveBits,
+    WrapKey,
+    UnwrapKey,
+}
+
+impl KeyUsage {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Encrypt => "encrypt",
+            Self::Decrypt => "decrypt",
+            Self::Sign => "sign",
+            Self::Verify => "verify",
+            Self::DeriveKey => "deriveKey",
+            Self::DeriveBits => "deriveBits",
+            Self::WrapKey => "wrapKey",
+            Self::UnwrapKey => "unwrapKey",
+        }
+    }
+}
+
+/// The format of key material.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    Raw,
+    Pkcs8,
+    Spki,
+    Jwk,
+}
+
+impl KeyFormat {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Raw => "raw",
+            Self::Pkcs8 => "pkcs8",
+            Self::Spki => "spki",
+            Self::Jwk => "jwk",
+        }
+    }
+}
+
+/// Key material in one of the [KeyFormat]s.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyData {
+    /// Raw bytes, used for symmetric keys and EC and Ed25519 public keys.
+    Raw(Vec<u8>),
+    /// A DER-encoded PKCS #8 private key.
+    Pkcs8(Vec<u8>),
+    /// A DER-encoded SubjectPublicKeyInfo public key.
+    Spki(Vec<u8>),
+    /// A JSON Web Key.
+    Jwk(serde_json::Value),
+}
+
+impl KeyData {
+    fn format(&self) -> KeyFormat {
+        match self {
+            Self::Raw(_) => KeyFormat::Raw,
+            Self::Pkcs8(_) => KeyFormat::Pkcs8,
+            Self::Spki(_) => KeyFormat::Spki,
+            Self::Jwk(_) => KeyFormat::Jwk,
+        }
+    }
+
+    fn to_js(&self) -> Result<JsObject> {
+        match self {
+            Self::Raw(bytes) | Self::Pkcs8(bytes) | Self::Spki(bytes) => {
+                Ok(Uint8Array::from(bytes.as_slice()).into())
+            },
+            Self::Jwk(jwk) => Ok(rpc::to_js(jwk)?.unchecked_into()),
+        }
+    }
+}
+
+/// A key imported with [import_key].
+#[derive(Debug, Clone)]
+pub struct CryptoKey(SendWrapper<web_sys::CryptoKey>);
+
+impl CryptoKey {
+    /// `secret`, `private` or `public`.
+    pub fn key_type(&self) -> String {
+        self.0.type_()
+    }
+
+    /// Whether the key may be exported with [export_key].
+    pub fn extractable(&self) -> bool {
+        self.0.extractable()
+    }
+
+    /// The names of the operations the key may be used for.
+    pub fn usages(&self) -> Vec<String> {
+        self.0
+            .usages()
+            .iter()
+            .filter_map(|usage| usage.as_string())
+            .collect()
+    }
+}
+
+impl AsRef<JsValue> for CryptoKey {
+    fn as_ref(&self) -> &JsValue {
+        &self.0
+    }
+}
+
+impl From<web_sys::CryptoKey> for CryptoKey {
+    fn from(key: web_sys::CryptoKey) -> Self {
+        Self(SendWrapper::new(key))
+    }
+}
+
+/// The algorithm of a signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureAlgorithm {
+    /// HMAC, with the hash of the key.
+    Hmac,
+    /// ECDSA with the given hash. Signatures are in the IEEE P1363 format.
+    Ecdsa {
+        hash: HashAlgorithm,
+    },
+    Ed25519,
+    /// RSA-PSS with the given salt length in bytes.
+    RsaPss {
+        salt_length: u32,
+    },
+    /// RSASSA-PKCS1-v1_5, as used by `RS256` JSON Web Tokens.
+    RsaPkcs1,
+}
+
+impl SignatureAlgorithm {
+    fn to_js(self) -> JsObject {
+        match self {
+            Self::Hmac => js_object! { "name" => "HMAC" },
+            Self::Ecdsa { hash } => js_object! { "name" => "ECDSA", "hash" => hash.name() },
+            Self::Ed25519 => js_object! { "name" => "Ed25519" },
+            Self::RsaPss { salt_length } => {
+                js_object! { "name" => "RSA-PSS", "saltLength" => salt_length }
+            },
+            Self::RsaPkcs1 => js_object! { "name" => "RSASSA-PKCS1-v1_5" },
+        }
+    }
+}
+
+/// An algorithm to derive bits from a key imported for HKDF or PBKDF2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeriveAlgorithm {
+    Hkdf {
+        hash: HashAlgorithm,
+        salt: Vec<u8>,
+        info: Vec<u8>,
+    },
+    Pbkdf2 {
+        hash: HashAlgorithm,
+        salt: Vec<u8>,
+        iterations: u32,
+    },
+}
+
+impl DeriveAlgorithm {
+    fn to_js(&self) -> JsObject {
+        match self {
+            Self::Hkdf { hash, salt, info } => js_object! {
+                "name" => "HKDF",
+                "hash" => hash.name(),
+                "salt" => Uint8Array::from(salt.as_slice()),
+                "info" => Uint8Array::from(info.as_slice()),
+            },
+            Self::Pbkdf2 {
+                hash,
+                salt,
+                iterations,
+            } => js_object! {
+                "name" => "PBKDF2",
+                "hash" => hash.name(),
+                "salt" => Uint8Array::from(salt.as_slice()),
+                "iterations" => *iterations,
+            },
+        }
+    }
+}
+
+/// Hashes `data`.
+pub async fn digest(algorithm: DigestAlgorithm, data: &[u8]) -> Result<Vec<u8>> {
+    let fut = {
+        let data = Uint8Array::from(data);
+        let promise = subtle()?
+            .digest_with_str_and_buffer_source(algorithm.name(), &data)
+            .map_err(WorkerError::from_js_err)?;
+        future_from_promise(promise)
+    };
+
+    let digest = fut.await.map_err(WorkerError::from_promise_err)?;
+    Ok(to_vec(digest))
+}
+
+/// Imports key material for use with the given algorithm.
+pub async fn import_key(
+    data: KeyData, algorithm: KeyAlgorithm, extractable: bool, usages: &[KeyUsage],
+) -> Result<CryptoKey> {
+    let fut = {
+        let usages = usages
+            .iter()
+            .map(|usage| JsValue::from_str(usage.name()))
+            .collect::<Array>();
+        let promise = subtle()?
+            .import_key_with_object(
+                data.format().name(),
+                &data.to_js()?,
+                &algorithm.to_js(),
+                extractable,
+                &usages,
+            )
+            .map_err(WorkerError::from_js_err)?;
+        future_from_promise(promise)
+    };
+
+    let key = fut.await.map_err(WorkerError::from_promise_err)?;
+    Ok(CryptoKey(SendWrapper::new(key.unchecked_into())))
+}
+
+/// Exports an extractable key.
+pub async fn export_key(key: &CryptoKey, format: KeyFormat) -> Result<KeyData> {
+    let fut = {
+        let promise = subtle()?
+            .export_key(format.name(), &key.0)
+            .map_err(WorkerError::from_js_err)?;
+        future_from_promise(promise)
+    };
+
+    let data = fut.await.map_err(WorkerError::from_promise_err)?;
+    Ok(match format {
+        KeyFormat::Raw => KeyData::Raw(to_vec(data)),
+        KeyFormat::Pkcs8 => KeyData::Pkcs8(to_vec(data)),
+        KeyFormat::Spki => KeyData::Spki(to_vec(data)),
+        KeyFormat::Jwk => KeyData::Jwk(rpc::from_js(data)?),
+    })
+}
+
+/// Signs `data` with a private or secret key.
+pub async fn sign(algorithm: SignatureAlgorithm, key: &CryptoKey, data: &[u8]) -> Result<Vec<u8>> {
+    let fut = {
+        let data = Uint8Array::from(data);
+        let promise = subtle()?
+            .sign_with_object_and_buffer_source(&algorithm.to_js(), &key.0, &data)
+            .map_err(WorkerError::from_js_err)?;
+        future_from_promise(promise)
+    };
+
+    let signature = fut.await.map_err(WorkerError::from_promise_err)?;
+    Ok(to_vec(signature))
+}
+
+/// Verifies the `signature` of `data` with a public or secret key.
+pub async fn verify(
+    algorithm: SignatureAlgorithm, key: &CryptoKey, signature: &[u8], data: &[u8],
+) -> Result<bool> {
+    let fut = {
+        let signature = Uint8Array::from(signature);
+        let data = Uint8Array::from(data);
+        let promise = subtle()?
+            .verify_with_object_and_buffer_source_and_buffer_source(
+                &algorithm.to_js(),
+                &key.0,
+                &signature,
+                &data,
+            )
+            .map_err(WorkerError::from_js_err)?;
+        future_from_promise(promise)
+    };
+
+    let valid = fut.await.map_err(WorkerError::from_promise_err)?;
+    Ok(valid.as_bool().unwrap_or(false))
+}
+
+/// Encrypts `data` with an AES-GCM key. The `iv` must never be reused with
+/// the same key; 12 random bytes from [get_random_values] are recommended.
+/// The authentication tag is appended to the returned ciphertext.
+pub async fn encrypt_aes_gcm(
+    key: &CryptoKey, iv: &[u8], data: &[u8], additional_data: Option<&[u8]>,
+) -> Result<Vec<u8>> {
+    let fut = {
+        let algorithm = aes_gcm_params(iv, additional_data);
+        let data = Uint8Array::from(data);
+        let promise = subtle()?
+            .encrypt_with_object_and_buffer_source(&algorithm, &key.0, &data)
+            .map_err(WorkerError::from_js_err)?;
+        future_from_promise(promise)
+    };
+
+    let ciphertext = fut.await.map_err(WorkerError::from_promise_err)?;
+    Ok(to_vec(ciphertext))
+}
+
+/// Decrypts and authenticates `data` encrypted with [encrypt_aes_gcm].
+pub async fn decrypt_aes_gcm(
+    key: &CryptoKey, iv: &[u8], data: &[u8], additional_data: Option<&[u8]>,
+) -> Result<Vec<u8>> {
+    let fut = {
+        let algorithm = aes_gcm_params(iv, additional_data);
+        let data = Uint8Array::from(data);
+        let promise = subtle()?
+            .decrypt_with_object_and_buffer_source(&algorithm, &key.0, &data)
+            .map_err(WorkerError::from_js_err)?;
+        future_from_promise(promise)
+    };
+
+    let plaintext = fut.await.map_err(WorkerError::from_promise_err)?;
+    Ok(to_vec(plaintext))
+}
+
+fn aes_gcm_params(iv: &[u8], additional_data: Option<&[u8]>) -> JsObject {
+    let params = js_object! {
+        "name" => "AES-GCM",
+        "iv" => Uint8Array::from(iv),
+    };
+    if let Some(additional_data) = additional_data {
+        let _ = js_sys::Reflect::set(
+            &params,
+            &JsValue::from_str("additionalData"),
+            &Uint8Array::from(additional_data),
+        );
+    }
+    params
+}
+
+/// Derives `length` bits from a key imported for HKDF or PBKDF2. `length`
+/// must be a multiple of 8.
+pub async fn derive_bits(
+    algorithm: &DeriveAlgorithm, key: &CryptoKey, length: u32,
+) -> Result<Vec<u8>> {
+    let fut = {
+        let promise = subtle()?
+            .derive_bits_with_object(&algorithm.to_js(), &key.0, length)
+            .map_err(WorkerError::from_js_err)?;
+        future_from_promise(promise)
+    };
+
+    let bits = fut.await.map_err(WorkerError::from_promise_err)?;
+    Ok(to_vec(bits))
+}
+
+/// Fills `buf` with cryptographically secure random bytes.
+pub fn get_random_values(buf: &mut [u8]) -> Result<()> {
+    let crypto = crypto()?;
+    for chunk in buf.chunks_mut(MAX_RANDOM_VALUES) {
+        crypto
+            .get_random_values_with_u8_array(chunk)
+            .map_err(WorkerError::from_js_err)?;
+    }
+    Ok(())
+}
+
+/// Generates a random version 4 UUID.
+pub fn random_uuid() -> Result<String> {
+    Ok(crypto()?.random_uuid())
+}
+
+/// Compares two byte slices in constant time, to avoid leaking secrets such
+/// as signatures through timing. Slices of different lengths are unequal.
+pub fn timing_safe_equal(a: &[u8], b: &[u8]) -> Result<bool> {
+    if a.len() != b.len() {
+        return Ok(false);
+    }
+
+    subtle()?
+        .timing_safe_equal(&Uint8Array::from(a), &Uint8Array::from(b))
+        .map_err(WorkerError::from_js_err)
+}
+
+/// A `WritableStream` that hashes the bytes written to it, for hashing data
+/// as it streams instead of buffering it for [digest].
+#[derive(Debug, Clone)]
+pub struct DigestStream(SendWrapper<DigestStreamSys>);
+
+impl DigestStream {
+    /// Creates a stream hashing with `algorithm`.
+    pub fn new(algorithm: DigestAlgorithm) -> Result<Self> {
+        let stream = DigestStreamSys::new(algorithm.name()).map_err(WorkerError::from_js_err)?;
+        Ok(Self(SendWrapper::new(stream)))
+    }
+
+    /// The stream to write the data to.
+    pub fn writable(&self) -> web_sys::WritableStream {
+        (*self.0).clone().unchecked_into()
+    }
+
+    /// The digest, which resolves once the stream is closed.
+    pub fn digest(&self) -> DigestFuture {
+        DigestFuture(future_from_promise(self.0.digest()))
+    }
+}
+
+impl AsRef<JsValue> for DigestStream {
+    fn as_ref(&self) -> &JsValue {
+        &self.0
+    }
+}
+
+/// The digest of a [DigestStream], which resolves once all the data has been
+/// written.
+pub struct DigestFuture(
+    Pin<Box<dyn Future<Output = std::result::Result<JsValue, JsValue>> + Send + Sync + 'static>>,
+);
+
+impl Future for DigestFuture {
+    type Output = Result<Vec<u8>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0
+            .as_mut()
+            .poll(cx)
+            .map_ok(to_vec)
+            .map_err(WorkerError::from_promise_err)
+    }
+}
+
+/// Hashes a body while it is read. Returns the body, which yields the same
+/// data, and its digest, which resolves once the body has been read to the
+/// end. The digest fails if the body errors or is dropped before its end, and
+/// the body fails if a chunk can't be written to the digest.
+///
+/// ```ignore
+/// let (body, md5) = crypto::digest_body(DigestAlgorithm::Md5, req.into_body())?;
+/// let stream = BodyDataStream::new(body).map_ok(|chunk| chunk.to_vec());
+/// bucket
+///     .put(key, FixedLengthStream::wrap(stream, length))
+///     .execute()
+///     .await?;
+/// let md5 = md5.await?;
+/// ```
+pub fn digest_body(algorithm: DigestAlgorithm, body: Body) -> Result<(Body, DigestFuture)> {
+    let stream = DigestStream::new(algorithm)?;
+    let digest = stream.digest();
+    let writer = stream
+        .writable()
+        .get_writer()
+        .map_err(WorkerError::from_js_err)?;
+
+    if body.size_hint().exact() == Some(0) {
+        let _ = writer.close();
+        return Ok((body, digest));
+    }
+
+    let body = Body::new(DigestBody {
+        inner: body,
+        writer: Some(SendWrapper::new(writer)),
+        write: None,
+    });
+    Ok((body, digest))
+}
+
+/// A body that writes its data into a [DigestStream] as it is read.
+///
+/// A chunk is written before the next one is read, so that a failed write
+/// fails the body and the digest instead of leaving the chunk out.
+struct DigestBody {
+    inner: Body,
+    writer: Option<SendWrapper<WritableStreamDefaultWriter>>,
+    /// The write of the last chunk read, until it finishes.
+    write: Option<SendWrapper<JsFuture>>,
+}
+
+impl HttpBody for DigestBody {
+    type Data = Bytes;
+    type Error = WorkerError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>, cx: &mut Context<'_>,
+    ) -> Poll<Option<std::result::Result<Frame<Bytes>, WorkerError>>> {
+        if let Some(write) = self.write.as_mut() {
+            let written = futures_util::ready!(write.poll_unpin(cx));
+            self.write = None;
+            if let Err(err) = written {
+                if let Some(writer) = self.writer.take() {
+                    let _ = writer.abort_with_reason(&err);
+                }
+                return Poll::Ready(Some(Err(WorkerError::from_promise_err(err))));
+            }
+        }
+
+        let frame = if self.inner.is_end_stream() {
+            None
+        } else {
+            futures_util::ready!(Pin::new(&mut self.inner).poll_frame(cx))
+        };
+        match &frame {
+            Some(Ok(frame)) => {
+                if let (Some(data), Some(writer)) = (frame.data_ref(), &self.writer) {
+                    let write = writer.write_with_chunk(&Uint8Array::from(data.as_ref()));
+                    self.write = Some(SendWrapper::new(JsFuture::from(write)));
+                }
+            },
+            Some(Err(_)) => {
+                if let Some(writer) = self.writer.take() {
+                    let _ = writer.abort();
+                }
+            },
+            None => {
+                if let Some(writer) = self.writer.take() {
+                    let _ = writer.close();
+                }
+            },
+        }
+        Poll::Ready(frame)
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+
+    fn is_end_stream(&self) -> bool {
+        // The writer is closed on the poll after the last chunk, once its write
+        // has finished.
+        self.inner.is_end_stream() && self.writer.is_none()
+    }
+}
+
+impl Drop for DigestBody {
+    fn drop(&mut self) {
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_algorithms() {
+        assert_eq!(DigestAlgorithm::Sha256.name(), "SHA-256");
+        assert_eq!(DigestAlgorithm::Md5.name(), "MD5");
+        assert_eq!(HashAlgorithm::Sha384.name(), "SHA-384");
+        assert_eq!(NamedCurve::P384.name(), "P-384");
+        assert_eq!(KeyUsage::DeriveBits.name(), "deriveBits");
+        assert_eq!(KeyData::Jwk(serde_json::json!({})).format(), KeyFormat::Jwk);
+    }
+}
@@ -12,80 +12,218 @@
 //! [Learn more](https://developers.cloudflare.com/workers/learning/using-durable-objects) about
 //! using Durable Objects.
 
-use std::convert::TryFrom;
+mod error;
+mod namespace;
+mod persist;
+#[cfg(feature = "durable-primitives")]
+pub mod primitives;
+mod sql;
+mod timers;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::{Infallible, TryFrom};
+use std::fmt;
 use std::ops::Deref;
+use std::rc::Rc;
 use std::time::Duration;
 
 use betterworker_sys::{
     DurableObject as EdgeDurableObject, DurableObjectId,
     DurableObjectNamespace as EdgeObjectNamespace, DurableObjectState, DurableObjectStorage,
-    DurableObjectTransaction,
+    DurableObjectTransaction, WebSocketRequestResponsePair,
 };
 use chrono::{DateTime, Utc};
-use futures_util::Future;
-use js_sys::{Map, Number, Object};
+pub use error::DurableObjectError;
+use futures_util::{stream, Future, Stream, TryStreamExt};
+use js_sys::{Array, Map, Number, Object, Reflect};
+pub use namespace::Namespace;
+pub use persist::{
+    flush_persisted, Codec, JsonCodec, PersistBatch, Persisted, PersistedFields,
+    StructuredCloneCodec,
+};
 use send_wrapper::SendWrapper;
-use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::de::{self, DeserializeOwned, DeserializeSeed};
+use serde::{Deserialize, Deserializer, Serialize};
+pub use sql::{SqlCursor, SqlRawCursor, SqlStorage, SqlValue, SyncKvStorage};
+pub use timers::Timers;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::future_to_promise;
 
 use crate::body::Body;
-use crate::date::Date;
+use crate::date::{Date, DateInit};
+use crate::delay::Delay;
 use crate::env::Env;
 use crate::error::WorkerError;
 use crate::futures::future_from_promise;
 use crate::http::{request, response};
 use crate::result::Result;
+use crate::websocket::{WebSocket, WebSocketIncomingMessage};
 
 /// A Durable Object stub is a client object used to send requests to a remote
 /// Durable Object.
+///
+/// A Stub keeps a handle to the namespace of the object, so it can be stored
+/// and reused across requests.
+#[derive(Clone)]
 pub struct Stub {
     inner: SendWrapper<EdgeDurableObject>,
+    namespace: ObjectNamespace,
+    retry: Option<RetryOptions>,
 }
 
 impl Stub {
+    fn new(inner: EdgeDurableObject, namespace: ObjectNamespace) -> Self {
+        Self {
+            inner: SendWrapper::new(inner),
+            namespace,
+            retry: None,
+        }
+    }
+
+    /// The id of the Durable Object to which the stub points.
+    pub fn id(&self) -> ObjectId {
+        ObjectId {
+            inner: SendWrapper::new(self.inner.id()),
+            namespace: self.namespace.clone(),
+        }
+    }
+
+    /// The name of the Durable Object, if the stub was created from a name.
+    pub fn name(&self) -> Option<String> {
+        self.inner.name()
+    }
+
+    /// Makes [`Stub::fetch_with_request`] retry the requests that fail with a
+    /// [`DurableObjectError`] that [should be
+    /// retried](DurableObjectError::should_retry).
+    ///
+    /// A failed stub can't be reused, so each retry is sent through a new stub
+    /// for the same object, after a delay growing exponentially with jitter.
+    /// The object may have handled the request before failing, so only
+    /// requests that are safe to repeat should be retried.
+    pub fn with_retry(mut self, options: RetryOptions) -> Self {
+        self.retry = Some(options);
+        self
+    }
+
     /// Send an internal Request to the Durable Object to which the stub points.
     pub async fn fetch_with_request(
         &self, req: http::Request<Body>,
     ) -> Result<http::Response<Body>> {
-        let fut = {
-            let req = request::into_web_sys_request(req);
-            let promise = self.inner.fetch_with_request(&req);
+        let req = request::into_web_sys_request(req);
+        let Some(retry) = &self.retry else {
+            return Self::send(&self.inner, &req).await;
+        };
+
+        let mut stub = EdgeDurableObject::clone(&self.inner);
+        let mut req = Some(req);
+        let mut attempt = 1;
+        loop {
+            let last = attempt >= retry.max_attempts;
+            // The body of a request can only be read once, so every attempt
+            // but the last one sends a copy.
+            let sent = match &req {
+                Some(req) if !last => req.clone().map_err(WorkerError::from_js_err)?,
+                _ => req.take().ok_or(WorkerError::BodyUsed)?,
+            };
+
+            match Self::send(&stub, &sent).await {
+                Err(WorkerError::DurableObject(err)) if err.should_retry() && !last => {
+                    Delay::from(retry.jittered_delay(attempt)).await;
+                    stub = self
+                        .namespace
+                        .0
+                        .get(&stub.id())
+                        .map_err(WorkerError::from_js_err)?;
+                    attempt += 1;
+                },
+                result => return result,
+            }
+        }
+    }
 
+    /// Construct a Request from a URL to the Durable Object to which the stub
+    /// points.
+    pub async fn fetch_with_str(&self, url: &str) -> Result<http::Response<Body>> {
+        let fut = {
+            let promise = self.inner.fetch_with_str(url);
             future_from_promise(promise)
         };
 
         let res = fut
             .await
-            .map_err(WorkerError::from_promise_err)?
+            .map_err(WorkerError::from_durable_object_err)?
             .dyn_into()
             .map_err(WorkerError::from_cast_err)?;
         Ok(response::from_web_sys_response(res))
     }
 
-    /// Construct a Request from a URL to the Durable Object to which the stub
-    /// points.
-    pub async fn fetch_with_str(&self, url: &str) -> Result<http::Response<Body>> {
+    async fn send(
+        stub: &EdgeDurableObject, req: &web_sys::Request,
+    ) -> Result<http::Response<Body>> {
         let fut = {
-            let promise = self.inner.fetch_with_str(url);
+            let promise = stub.fetch_with_request(req);
             future_from_promise(promise)
         };
 
         let res = fut
             .await
-            .map_err(WorkerError::from_promise_err)?
+            .map_err(WorkerError::from_durable_object_err)?
             .dyn_into()
             .map_err(WorkerError::from_cast_err)?;
         Ok(response::from_web_sys_response(res))
     }
 }
 
+/// How a [`Stub`] retries failed requests, see [`Stub::with_retry`].
+#[derive(Debug, Clone)]
+pub struct RetryOptions {
+    /// The maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// The delay before the first retry. It doubles with every retry.
+    pub base_delay: Duration,
+    /// The maximum delay between two attempts.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryOptions {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(3),
+        }
+    }
+}
+
+impl RetryOptions {
+    /// The delay after the failed attempt `attempt`, counting from 1.
+    fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt - 1).unwrap_or(u32::MAX);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// A random delay between half and all of [`Self::delay`], so that the
+    /// retries of concurrent requests don't all hit the object at once.
+    fn jittered_delay(&self, attempt: u32) -> Duration {
+        let delay = self.delay(attempt);
+        delay / 2 + delay.mul_f64(js_sys::Math::random() / 2.0)
+    }
+}
+
+impl AsRef<JsValue> for Stub {
+    fn as_ref(&self) -> &JsValue {
+        &self.inner
+    }
+}
+
 /// Use an ObjectNamespace to get access to Stubs for communication with a
 /// Durable Object instance. A given namespace can support essentially unlimited
 /// Durable Objects, with each Object having access to a transactional,
 /// key-value storage API.
+#[derive(Clone)]
 pub struct ObjectNamespace(SendWrapper<EdgeObjectNamespace>);
 
 impl TryFrom<Object> for ObjectNamespace {
@@ -124,14 +262,14 @@ impl ObjectNamespace {
             .map_err(WorkerError::from_js_err)
             .map(|id| ObjectId {
                 inner: SendWrapper::new(id),
-                namespace: Some(SendWrapper::new(self)),
+                namespace: self.clone(),
             })
     }
 
     /// This method parses an ID that was previously stringified. This is useful
     /// in particular with IDs created using `unique_id(&self)`, as these
-    /// IDs need to be stored somewhere, probably as
-    // as a string.
+    /// IDs need to be stored somewhere, probably as a string. [`ObjectId`] and
+    /// [`StateId`] display and serialize as this string.
     /// A stringified object ID is a 64-digit hexadecimal number. However, not
     /// all 64-digit hex numbers are valid IDs. This method will throw if it
     /// is passed an ID that was not originally created by newUniqueId() or
@@ -143,10 +281,23 @@ impl ObjectNamespace {
             .map_err(WorkerError::from_js_err)
             .map(|id| ObjectId {
                 inner: SendWrapper::new(id),
-                namespace: Some(SendWrapper::new(self)),
+                namespace: self.clone(),
             })
     }
 
+    /// Deserializes an [`ObjectId`] of this namespace from the hex string it
+    /// serializes as, checking it with
+    /// [`id_from_string`](Self::id_from_string).
+    ///
+    /// ```ignore
+    /// use serde::de::DeserializeSeed;
+    ///
+    /// let id = namespace.id_seed().deserialize(&mut serde_json::Deserializer::from_str(&json))?;
+    /// ```
+    pub fn id_seed(&self) -> ObjectIdSeed<'_> {
+        ObjectIdSeed(self)
+    }
+
     /// Creates a new object ID randomly. This method will never return the same
     /// ID twice, and thus it is guaranteed that the object does not yet
     /// exist and has never existed at the time the method returns.
@@ -156,7 +307,7 @@ impl ObjectNamespace {
             .map_err(WorkerError::from_js_err)
             .map(|id| ObjectId {
                 inner: SendWrapper::new(id),
-                namespace: Some(SendWrapper::new(self)),
+                namespace: self.clone(),
             })
     }
 
@@ -180,55 +331,244 @@ impl ObjectNamespace {
             .map_err(WorkerError::from_js_err)
             .map(|id| ObjectId {
                 inner: SendWrapper::new(id),
-                namespace: Some(SendWrapper::new(self)),
+                namespace: self.clone(),
             })
     }
+
+    /// Returns the subnamespace of the objects restricted to the jurisdiction
+    /// `jd`. The ids of the subnamespace, including those created with
+    /// `id_from_name()`, are all within the jurisdiction.
+    ///
+    /// See supported jurisdictions and more documentation at:
+    /// <https://developers.cloudflare.com/durable-objects/reference/data-location/#restrict-durable-objects-to-a-jurisdiction>
+    pub fn jurisdiction(&self, jd: &str) -> Result<ObjectNamespace> {
+        self.0
+            .jurisdiction(jd)
+            .map_err(WorkerError::from_js_err)
+            .map(|namespace| Self(SendWrapper::new(namespace)))
+    }
+
+    /// Gets a Stub for the Durable Object with the given name, in one step.
+    /// This is equivalent to `id_from_name(name)?.get_stub()`.
+    pub fn get_by_name(&self, name: &str) -> Result<Stub> {
+        self.0
+            .get_by_name(name)
+            .map_err(WorkerError::from_js_err)
+            .map(|data| Stub::new(data, self.clone()))
+    }
+
+    /// Gets a Stub for the Durable Object with the given name, in one step.
+    /// The options only apply if the object is created by this call.
+    pub fn get_by_name_with_options(&self, name: &str, options: GetStubOptions) -> Result<Stub> {
+        self.0
+            .get_by_name_with_options(name, &serde_wasm_bindgen::to_value(&options)?)
+            .map_err(WorkerError::from_js_err)
+            .map(|data| Stub::new(data, self.clone()))
+    }
 }
 
 /// An ObjectId is used to identify, locate, and access a Durable Object via
 /// interaction with its Stub.
-pub struct ObjectId<'a> {
+///
+/// An ObjectId keeps a handle to the namespace it was created with, so it can
+/// be stored and moved around freely. It serializes as its hex string, which
+/// [`ObjectNamespace::id_from_string`] parses back. It can't implement
+/// `Deserialize` since parsing needs the namespace, so use the seed returned by
+/// [`ObjectNamespace::id_seed`] instead.
+#[derive(Clone)]
+pub struct ObjectId {
     inner: SendWrapper<DurableObjectId>,
-    namespace: Option<SendWrapper<&'a ObjectNamespace>>,
+    namespace: ObjectNamespace,
 }
 
-impl ObjectId<'_> {
+impl ObjectId {
     /// Get a Stub for the Durable Object instance identified by this ObjectId.
     pub fn get_stub(&self) -> Result<Stub> {
-        self.namespace
-            .as_ref()
-            .ok_or_else(|| WorkerError::DurableObjectStub)
-            .and_then(|n| {
-                let data = n.0.get(&self.inner).map_err(WorkerError::from_js_err)?;
-                let stub = Stub {
-                    inner: SendWrapper::new(data),
-                };
-                Ok(stub)
-            })
+        let data = self
+            .namespace
+            .0
+            .get(&self.inner)
+            .map_err(WorkerError::from_js_err)?;
+        Ok(Stub::new(data, self.namespace.clone()))
+    }
+
+    /// Get a Stub for the Durable Object instance identified by this ObjectId,
+    /// with options that only apply if the object is created by this call.
+    pub fn get_stub_with_options(&self, options: GetStubOptions) -> Result<Stub> {
+        let options = serde_wasm_bindgen::to_value(&options)?;
+        let data = self
+            .namespace
+            .0
+            .get_with_options(&self.inner, &options)
+            .map_err(WorkerError::from_js_err)?;
+        Ok(Stub::new(data, self.namespace.clone()))
+    }
+
+    /// The name the id was derived from with `id_from_name()`, if known. Ids
+    /// created with `unique_id()` have no name.
+    pub fn name(&self) -> Option<String> {
+        self.inner.name()
+    }
+
+    /// The namespace of the Durable Object.
+    pub fn namespace(&self) -> &ObjectNamespace {
+        &self.namespace
+    }
+}
+
+impl PartialEq for ObjectId {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.equals(&other.inner)
+    }
+}
+
+impl PartialEq<StateId> for ObjectId {
+    fn eq(&self, other: &StateId) -> bool {
+        self.inner.equals(&other.0)
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.inner.to_string())
+    }
+}
+
+impl fmt::Debug for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ObjectId").field(&self.to_string()).finish()
     }
 }
 
-impl ToString for ObjectId<'_> {
-    fn to_string(&self) -> String {
-        self.inner.to_string()
+impl Serialize for ObjectId {
+    fn serialize<S: serde::Serializer>(
+        &self, serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
     }
 }
 
+/// Deserializes an [`ObjectId`] of a namespace, returned by
+/// [`ObjectNamespace::id_seed`].
+#[derive(Clone, Copy)]
+pub struct ObjectIdSeed<'a>(&'a ObjectNamespace);
+
+impl<'de> DeserializeSeed<'de> for ObjectIdSeed<'_> {
+    type Value = ObjectId;
+
+    fn deserialize<D: Deserializer<'de>>(
+        self, deserializer: D,
+    ) -> std::result::Result<ObjectId, D::Error> {
+        let hex_id = String::deserialize(deserializer)?;
+        self.0.id_from_string(&hex_id).map_err(de::Error::custom)
+    }
+}
+
+/// The id of a Durable Object as seen from inside it, returned by
+/// [`State::id`].
+///
+/// Unlike an [`ObjectId`], it isn't tied to a namespace, so it can't be used
+/// to get a Stub directly. Use [`StateId::with_namespace`] with the binding of
+/// the object's own class to get one. Its serialized hex string parses back as
+/// an [`ObjectId`] of that namespace, the same way.
+#[derive(Clone)]
+pub struct StateId(SendWrapper<DurableObjectId>);
+
+impl StateId {
+    /// The name the id was derived from with `id_from_name()`, if known.
+    pub fn name(&self) -> Option<String> {
+        self.0.name()
+    }
+
+    /// Returns the same id as an [`ObjectId`] of `namespace`, which must be
+    /// the namespace of the object.
+    pub fn with_namespace(&self, namespace: &ObjectNamespace) -> Result<ObjectId> {
+        namespace.id_from_string(&self.to_string())
+    }
+}
+
+impl PartialEq for StateId {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.equals(&other.0)
+    }
+}
+
+impl PartialEq<ObjectId> for StateId {
+    fn eq(&self, other: &ObjectId) -> bool {
+        self.0.equals(&other.inner)
+    }
+}
+
+impl fmt::Display for StateId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.to_string())
+    }
+}
+
+impl fmt::Debug for StateId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StateId").field(&self.to_string()).finish()
+    }
+}
+
+impl Serialize for StateId {
+    fn serialize<S: serde::Serializer>(
+        &self, serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Options of [`ObjectId::get_stub_with_options`] and
+/// [`ObjectNamespace::get_by_name_with_options`].
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetStubOptions {
+    /// Where to create the object, if it doesn't exist yet. The hint is
+    /// best-effort and is ignored for existing objects.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location_hint: Option<LocationHint>,
+}
+
+/// A region where a Durable Object can be created.
+///
+/// [CF Documentation](https://developers.cloudflare.com/durable-objects/reference/data-location/#provide-a-location-hint)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LocationHint {
+    /// Western North America.
+    Wnam,
+    /// Eastern North America.
+    Enam,
+    /// South America.
+    Sam,
+    /// Western Europe.
+    Weur,
+    /// Eastern Europe.
+    Eeur,
+    /// Asia-Pacific.
+    Apac,
+    /// Oceania.
+    Oc,
+    /// Africa.
+    Afr,
+    /// Middle East.
+    Me,
+}
+
 /// Passed from the runtime to provide access to the Durable Object's storage as
 /// well as various metadata about the Object.
 #[derive(Clone)]
 pub struct State {
     inner: DurableObjectState,
+    class_name: Option<&'static str>,
 }
 
 impl State {
     /// The ID of this Durable Object which can be converted into a hex string
     /// using its `to_string()` method.
-    pub fn id(&self) -> ObjectId<'_> {
-        ObjectId {
-            inner: SendWrapper::new(self.inner.id()),
-            namespace: None,
-        }
+    pub fn id(&self) -> StateId {
+        StateId(SendWrapper::new(self.inner.id()))
     }
 
     /// Contains methods for accessing persistent storage via the transactional
@@ -240,7 +580,8 @@ impl State {
     }
 
     /// Executes the [`Future`] while blocking any other events
-    /// from being delivered to the object until the callback completes.
+    /// from being delivered to the object until the callback completes, and
+    /// returns its result.
     ///
     /// This allows you to execute some code that performs I/O (such as a call
     /// to the durable object fetch()) with the guarantee that the object’s
@@ -253,31 +594,216 @@ impl State {
     /// [`Self::block_concurrency_while()`] is especially useful within the
     /// constructor of your Object to perform initialization that must occur
     /// before any requests are delivered.
-    pub fn block_concurrency_while<F>(&self, future: F)
+    ///
+    /// The block starts when this is called rather than when the returned
+    /// future is first polled, so the future can be dropped in
+    /// [`DurableObject::new`], which can't await it. Unlike in JavaScript, an
+    /// `Err` doesn't reset the object: it is returned, and
+    /// [`Self::abort`] resets the object if it can't recover.
+    ///
+    /// ```ignore
+    /// let config = state
+    ///     .block_concurrency_while(async move { storage.get_opt::<Config>("config").await })
+    ///     .await?;
+    /// ```
+    pub fn block_concurrency_while<F, T>(
+        &self, future: F,
+    ) -> impl Future<Output = Result<T>> + 'static
+    where
+        F: Future<Output = Result<T>> + 'static,
+        T: 'static, {
+        let result = SendWrapper::new(Rc::new(RefCell::new(None)));
+        let promise = {
+            let result = Rc::clone(&result);
+            let callback = Closure::once_into_js(move || {
+                future_to_promise(async move {
+                    *result.borrow_mut() = Some(future.await);
+                    Ok(JsValue::UNDEFINED)
+                })
+            });
+            self.inner
+                .block_concurrency_while(callback.unchecked_ref())
+                .map(|promise| SendWrapper::new(future_from_promise(promise)))
+                .map_err(WorkerError::from_js_err)
+        };
+
+        async move {
+            let completed = promise?.await;
+            let outcome = result.borrow_mut().take();
+            match (completed, outcome) {
+                (_, Some(outcome)) => outcome,
+                (Err(err), None) => Err(WorkerError::from_promise_err(err)),
+                (Ok(_), None) => Err(WorkerError::JsError(
+                    "block_concurrency_while completed without running its future".into(),
+                )),
+            }
+        }
+    }
+
+    /// Extends the lifetime of the current event until the given future has
+    /// been completed, without delaying its response.
+    pub fn wait_until<F>(&self, future: F)
     where
         F: Future<Output = ()> + 'static, {
+        self.inner.wait_until(&future_to_promise(async {
+            future.await;
+            Ok(JsValue::UNDEFINED)
+        }))
+    }
+
+    /// Resets the Durable Object, discarding its in-memory state, for example
+    /// after detecting that it is corrupted.
+    ///
+    /// The events in progress fail with an error carrying `reason`, and the
+    /// next event is delivered to a new instance of the object, which also
+    /// applies the bookmark passed to
+    /// [`Storage::on_next_session_restore_bookmark`], if any.
+    ///
+    /// The runtime signals the reset by throwing, which is returned as the
+    /// error here. Propagate it with `?` so the handler returns right away:
+    ///
+    /// ```ignore
+    /// self.state.abort("corrupted state")?;
+    /// ```
+    pub fn abort(&self, reason: &str) -> Result<Infallible> {
+        match self.inner.abort(reason) {
+            Err(err) => Err(WorkerError::from_js_err(err)),
+            Ok(()) => Err(WorkerError::JsError(reason.into())),
+        }
+    }
+
+    /// The name of the class of the Durable Object, or `None` if the `State`
+    /// wasn't created by `#[durable_object]`.
+    pub fn class_name(&self) -> Option<&'static str> {
+        self.class_name
+    }
+
+    /// The namespace of the Durable Object itself, to get stubs for other
+    /// objects of the same class without a binding.
+    ///
+    /// This requires the `enable_ctx_exports` compatibility flag. Otherwise,
+    /// get the namespace from its binding in [`Env`].
+    pub fn namespace(&self) -> Result<ObjectNamespace> {
+        let class_name = self
+            .class_name
+            .ok_or_else(|| WorkerError::UndefinedBinding("ctx.exports".into()))?;
+        let exports = self
+            .inner
+            .exports()
+            .ok_or_else(|| WorkerError::UndefinedBinding("ctx.exports".into()))?;
+        let namespace = Reflect::get(&exports, &JsValue::from_str(class_name))
+            .map_err(WorkerError::from_js_err)?;
+        if namespace.is_undefined() {
+            return Err(WorkerError::UndefinedBinding(class_name.into()));
+        }
+
+        ObjectNamespace::try_from(namespace.unchecked_into::<Object>())
+    }
+
+    /// Accepts a server-side `WebSocket` with the
+    /// [WebSocket Hibernation API](https://developers.cloudflare.com/durable-objects/best-practices/websockets/#websocket-hibernation-api).
+    /// The Durable Object can be evicted from memory while the `WebSocket`
+    /// stays connected, and its events are delivered to the
+    /// [`DurableObject::websocket_message`], [`DurableObject::websocket_close`]
+    /// and [`DurableObject::websocket_error`] handlers instead of event
+    /// listeners.
+    ///
+    /// `tags` can be used to look the `WebSocket` up with
+    /// [`Self::get_web_sockets`].
+    pub fn accept_web_socket(&self, ws: &WebSocket, tags: &[&str]) -> Result<()> {
+        let tags = tags
+            .iter()
+            .map(|tag| JsValue::from_str(tag))
+            .collect::<js_sys::Array>();
         self.inner
-            .block_concurrency_while(&future_to_promise(async {
-                future.await;
-                Ok(JsValue::UNDEFINED)
-            }))
+            .accept_web_socket(ws.as_ref(), &tags)
+            .map_err(WorkerError::from_js_err)
+    }
+
+    /// Returns the `WebSockets` accepted with [`Self::accept_web_socket`],
+    /// optionally only those with the given tag.
+    pub fn get_web_sockets(&self, tag: Option<&str>) -> Result<Vec<WebSocket>> {
+        let sockets = self
+            .inner
+            .get_web_sockets(tag)
+            .map_err(WorkerError::from_js_err)?;
+        Ok(sockets
+            .iter()
+            .map(|ws| WebSocket::from(ws.unchecked_into::<web_sys::WebSocket>()))
+            .collect())
+    }
+
+    /// Returns the tags a `WebSocket` was accepted with.
+    pub fn get_tags(&self, ws: &WebSocket) -> Result<Vec<String>> {
+        let tags = self
+            .inner
+            .get_tags(ws.as_ref())
+            .map_err(WorkerError::from_js_err)?;
+        Ok(tags.iter().filter_map(|tag| tag.as_string()).collect())
+    }
+
+    /// Responds with `response` to every message equal to `request` on the
+    /// hibernatable `WebSockets`, without waking up the Durable Object. This is
+    /// useful for keep-alive pings.
+    pub fn set_web_socket_auto_response(&self, request: &str, response: &str) -> Result<()> {
+        let pair = WebSocketRequestResponsePair::new(request, response)
+            .map_err(WorkerError::from_js_err)?;
+        self.inner
+            .set_web_socket_auto_response(Some(&pair))
+            .map_err(WorkerError::from_js_err)
+    }
+
+    /// Removes the automatic response set with
+    /// [`Self::set_web_socket_auto_response`].
+    pub fn clear_web_socket_auto_response(&self) -> Result<()> {
+        self.inner
+            .set_web_socket_auto_response(None)
+            .map_err(WorkerError::from_js_err)
+    }
+
+    /// Returns the request and response set with
+    /// [`Self::set_web_socket_auto_response`], if any.
+    pub fn get_web_socket_auto_response(&self) -> Option<(String, String)> {
+        self.inner
+            .get_web_socket_auto_response()
+            .map(|pair| (pair.request(), pair.response()))
+    }
+
+    /// Returns the last time a `WebSocket` was sent an automatic response, if
+    /// ever.
+    pub fn get_web_socket_auto_response_timestamp(&self, ws: &WebSocket) -> Result<Option<Date>> {
+        let timestamp = self
+            .inner
+            .get_web_socket_auto_response_timestamp(ws.as_ref())
+            .map_err(WorkerError::from_js_err)?;
+        Ok(timestamp.map(|date| DateInit::Millis(date.get_time() as u64).into()))
     }
 
     #[doc(hidden)]
     pub fn _inner(self) -> DurableObjectState {
         self.inner
     }
+
+    #[doc(hidden)]
+    pub fn __with_class_name(mut self, class_name: &'static str) -> Self {
+        self.class_name = Some(class_name);
+        self
+    }
 }
 
 impl From<DurableObjectState> for State {
     fn from(o: DurableObjectState) -> Self {
-        Self { inner: o }
+        Self {
+            inner: o,
+            class_name: None,
+        }
     }
 }
 
 /// Access a Durable Object's Storage API. Each method is implicitly wrapped
 /// inside a transaction, such that its results are atomic and isolated from all
 /// other storage operations, even when accessing multiple key-value pairs.
+#[derive(Clone)]
 pub struct Storage {
     inner: SendWrapper<DurableObjectStorage>,
 }
@@ -301,6 +827,77 @@ impl Storage {
             .map_err(WorkerError::from_js_err)
     }
 
+    /// Retrieves the value associated with the given key, or `None` if the key
+    /// does not exist.
+    pub async fn get_opt<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let fut = {
+            let promise = self.inner.get(key).map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        let value = fut.await.map_err(WorkerError::from_promise_err)?;
+        if value.is_undefined() {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_wasm_bindgen::from_value(value)?))
+    }
+
+    /// Retrieves the values associated with each of the provided keys. Keys
+    /// that do not exist are left out of the returned map.
+    pub async fn get_many<T: DeserializeOwned>(
+        &self, keys: Vec<impl Deref<Target = str>>,
+    ) -> Result<HashMap<String, T>> {
+        let map = self.get_multiple(keys).await?;
+        Ok(map_entries(&map)?.into_iter().collect())
+    }
+
+    /// Retrieves the value associated with the given key, or `None` if the key
+    /// does not exist.
+    pub async fn get_with_options<T: DeserializeOwned>(
+        &self, key: &str, options: GetOptions,
+    ) -> Result<Option<T>> {
+        let fut = {
+            let promise = self
+                .inner
+                .get_with_options(key, serde_wasm_bindgen::to_value(&options)?.into())
+                .map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        let value = fut.await.map_err(WorkerError::from_promise_err)?;
+        if value.is_undefined() {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_wasm_bindgen::from_value(value)?))
+    }
+
+    /// Retrieves the values associated with each of the provided keys. Keys
+    /// that do not exist are left out of the returned map.
+    pub async fn get_many_with_options<T: DeserializeOwned>(
+        &self, keys: Vec<impl Deref<Target = str>>, options: GetOptions,
+    ) -> Result<HashMap<String, T>> {
+        let fut = {
+            let promise = self
+                .inner
+                .get_multiple_with_options(
+                    keys.into_iter()
+                        .map(|key| JsValue::from(key.deref()))
+                        .collect(),
+                    serde_wasm_bindgen::to_value(&options)?.into(),
+                )
+                .map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        let map = fut
+            .await
+            .and_then(|jsv| jsv.dyn_into::<Map>())
+            .map_err(WorkerError::from_promise_err)?;
+        Ok(map_entries(&map)?.into_iter().collect())
+    }
+
     /// Retrieves the values associated with each of the provided keys.
     pub async fn get_multiple(&self, keys: Vec<impl Deref<Target = str>>) -> Result<Map> {
         let fut = {
@@ -346,6 +943,44 @@ impl Storage {
         fut.await.map_err(WorkerError::from_promise_err).map(|_| ())
     }
 
+    /// Stores the value and associates it with the given key.
+    pub async fn put_with_options<T: Serialize>(
+        &mut self, key: &str, value: T, options: PutOptions,
+    ) -> Result<()> {
+        let promise = self
+            .inner
+            .put_with_options(
+                key,
+                serde_wasm_bindgen::to_value(&value)?,
+                serde_wasm_bindgen::to_value(&options)?.into(),
+            )
+            .map_err(WorkerError::from_js_err)?;
+        let fut = future_from_promise(promise);
+        fut.await.map_err(WorkerError::from_promise_err).map(|_| ())
+    }
+
+    /// Takes a serializable struct and stores each of its keys and values to
+    /// storage.
+    pub async fn put_multiple_with_options<T: Serialize>(
+        &mut self, values: T, options: PutOptions,
+    ) -> Result<()> {
+        let fut = {
+            let values = serde_wasm_bindgen::to_value(&values)?;
+            if !values.is_object() {
+                return Err(WorkerError::MustPassInStructType);
+            }
+            future_from_promise(
+                self.inner
+                    .put_multiple_with_options(
+                        values,
+                        serde_wasm_bindgen::to_value(&options)?.into(),
+                    )
+                    .map_err(WorkerError::from_js_err)?,
+            )
+        };
+        fut.await.map_err(WorkerError::from_promise_err).map(|_| ())
+    }
+
     /// Deletes the key and associated value. Returns true if the key existed or
     /// false if it didn't.
     pub async fn delete(&mut self, key: &str) -> Result<bool> {
@@ -383,6 +1018,46 @@ impl Storage {
             .map_err(WorkerError::from_promise_err)
     }
 
+    /// Deletes the key and associated value. Returns true if the key existed or
+    /// false if it didn't.
+    pub async fn delete_with_options(&mut self, key: &str, options: PutOptions) -> Result<bool> {
+        let fut = self
+            .inner
+            .delete_with_options(key, serde_wasm_bindgen::to_value(&options)?.into())
+            .map(future_from_promise)
+            .map_err(WorkerError::from_js_err)?;
+        fut.await
+            .and_then(|jsv| {
+                jsv.as_bool()
+                    .ok_or_else(|| JsValue::from("Promise did not return bool"))
+            })
+            .map_err(WorkerError::from_promise_err)
+    }
+
+    /// Deletes the provided keys and their associated values. Returns a count
+    /// of the number of key-value pairs deleted.
+    pub async fn delete_multiple_with_options(
+        &mut self, keys: Vec<impl Deref<Target = str>>, options: PutOptions,
+    ) -> Result<usize> {
+        let fut = self
+            .inner
+            .delete_multiple_with_options(
+                keys.into_iter()
+                    .map(|key| JsValue::from(key.deref()))
+                    .collect(),
+                serde_wasm_bindgen::to_value(&options)?.into(),
+            )
+            .map(future_from_promise)
+            .map_err(WorkerError::from_js_err)?;
+        fut.await
+            .and_then(|jsv| {
+                jsv.as_f64()
+                    .map(|f| f as usize)
+                    .ok_or_else(|| JsValue::from("Promise did not return number"))
+            })
+            .map_err(WorkerError::from_promise_err)
+    }
+
     /// Deletes all keys and associated values, effectively deallocating all
     /// storage used by the Durable Object. In the event of a failure while
     /// the operation is still in flight, it may be that only a subset of
@@ -396,35 +1071,120 @@ impl Storage {
         fut.await.map(|_| ()).map_err(WorkerError::from_promise_err)
     }
 
-    /// Returns all keys and values associated with the current Durable Object
-    /// in ascending lexicographic sorted order.
+    /// Waits until all pending writes have been confirmed as durable.
+    ///
+    /// Writes are normally confirmed before the response of the Durable Object
+    /// is sent, so this is only needed to wait for writes made with
+    /// [`PutOptions::allow_unconfirmed`], or to confirm writes before doing
+    /// something other than responding.
+    pub async fn sync(&self) -> Result<()> {
+        let fut = self
+            .inner
+            .sync()
+            .map(future_from_promise)
+            .map_err(WorkerError::from_js_err)?;
+        fut.await.map(|_| ()).map_err(WorkerError::from_promise_err)
+    }
+
+    /// Returns all keys and values associated with the current Durable Object,
+    /// ordered by key.
     ///
     /// Be aware of how much data may be stored in your Durable Object before
     /// calling this version of list without options, because it will all be
     /// loaded into the Durable Object's memory, potentially hitting its [limit](https://developers.cloudflare.com/workers/platform/limits#durable-objects-limits).
-    /// If that is a concern, use the alternate `list_with_options()` method.
-    pub async fn list(&self) -> Result<Map> {
+    /// If that is a concern, use the alternate `list_with_options()` or
+    /// `list_stream()` methods.
+    pub async fn list<T: DeserializeOwned>(&self) -> Result<BTreeMap<String, T>> {
         let fut = self
             .inner
             .list()
             .map(future_from_promise)
             .map_err(WorkerError::from_js_err)?;
-        fut.await
-            .and_then(|jsv| jsv.dyn_into())
-            .map_err(WorkerError::from_promise_err)
+        let map = fut
+            .await
+            .and_then(|jsv| jsv.dyn_into::<Map>())
+            .map_err(WorkerError::from_promise_err)?;
+        Ok(map_entries(&map)?.into_iter().collect())
+    }
+
+    /// Returns keys and values associated with the current Durable Object
+    /// according to the parameters in the provided options object, ordered by
+    /// key.
+    ///
+    /// With [`ListOptions::reverse`], the returned entries are those at the end
+    /// of the range, but the map still iterates them in ascending order.
+    pub async fn list_with_options<T: DeserializeOwned>(
+        &self, opts: ListOptions,
+    ) -> Result<BTreeMap<String, T>> {
+        Ok(self.list_entries(opts).await?.into_iter().collect())
+    }
+
+    /// Returns a stream of the keys and values matching the options, in the
+    /// order they are listed. The entries are fetched in pages of `page_size`
+    /// keys, so that only one page is kept in memory at a time.
+    ///
+    /// ```ignore
+    /// let users = storage.list_stream::<User>(ListOptions::new().prefix("user:"), 128);
+    /// let mut users = std::pin::pin!(users);
+    /// while let Some((key, user)) = users.try_next().await? {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn list_stream<T: DeserializeOwned>(
+        &self, opts: ListOptions, page_size: usize,
+    ) -> impl Stream<Item = Result<(String, T)>> + '_ {
+        let page_size = page_size.max(1);
+        let remaining = opts.limit;
+
+        stream::try_unfold(Some((opts, remaining)), move |state| async move {
+            let Some((mut opts, remaining)) = state else {
+                return Ok::<_, WorkerError>(None);
+            };
+            let limit = remaining.map_or(page_size, |remaining| remaining.min(page_size));
+            if limit == 0 {
+                return Ok(None);
+            }
+
+            let page: Vec<(String, T)> = self.list_entries(opts.clone().limit(limit)).await?;
+            let next = match page.last() {
+                Some((last, _)) if page.len() == limit => {
+                    // `start` is inclusive and `end` exclusive, so continue right after the
+                    // last key.
+                    if opts.reverse == Some(true) {
+                        opts.end = Some(last.clone());
+                    } else {
+                        opts.start = Some(format!("{last}\0"));
+                    }
+                    Some((opts, remaining.map(|remaining| remaining - limit)))
+                },
+                _ => None,
+            };
+
+            Ok(Some((page, next)))
+        })
+        .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+        .try_flatten()
     }
 
-    /// Returns keys associated with the current Durable Object according to the
-    /// parameters in the provided options object.
-    pub async fn list_with_options(&self, opts: ListOptions<'_>) -> Result<Map> {
+    /// Returns a view of the keys starting with `prefix`, whose values are of
+    /// type `T`.
+    pub fn namespace<T>(&self, prefix: impl Into<String>) -> Namespace<T> {
+        Namespace::new(self.clone(), prefix)
+    }
+
+    async fn list_entries<T: DeserializeOwned>(
+        &self, opts: ListOptions,
+    ) -> Result<Vec<(String, T)>> {
         let fut = self
             .inner
             .list_with_options(serde_wasm_bindgen::to_value(&opts)?.into())
             .map(future_from_promise)
             .map_err(WorkerError::from_js_err)?;
-        fut.await
-            .and_then(|jsv| jsv.dyn_into())
-            .map_err(WorkerError::from_promise_err)
+        let map = fut
+            .await
+            .and_then(|jsv| jsv.dyn_into::<Map>())
+            .map_err(WorkerError::from_promise_err)?;
+        map_entries(&map)
     }
 
     /// Retrieves the current alarm time (if set) as integer milliseconds since
@@ -504,16 +1264,174 @@ impl Storage {
             .map_err(WorkerError::from_js_err)?;
         fut.await.map(|_| ()).map_err(WorkerError::from_promise_err)
     }
+
+    /// The SQL database of a SQLite-backed Durable Object.
+    pub fn sql(&self) -> SqlStorage {
+        SqlStorage::new(self.inner.sql())
+    }
+
+    /// The synchronous key-value API of a SQLite-backed Durable Object.
+    pub fn kv(&self) -> SyncKvStorage {
+        SyncKvStorage::new(self.inner.kv())
+    }
+
+    /// Runs `closure` synchronously in a transaction of a SQLite-backed
+    /// Durable Object and returns its result. The transaction is committed if
+    /// the closure returns `Ok` and rolled back if it returns `Err`.
+    ///
+    /// Only the synchronous [`SqlStorage`] and [`SyncKvStorage`] APIs can be
+    /// used inside the closure.
+    pub fn transaction_sync<F, T>(&self, closure: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T> + 'static,
+        T: 'static, {
+        let outcome = Rc::new(RefCell::new(None));
+        let result = {
+            let outcome = Rc::clone(&outcome);
+            let closure = Closure::once(move || {
+                let result = closure();
+                // Throwing rolls the transaction back.
+                let rejection = result
+                    .as_ref()
+                    .err()
+                    .map(|err| JsValue::from(err.to_string()));
+                *outcome.borrow_mut() = Some(result);
+                rejection.map_or(Ok(()), Err)
+            });
+            let closure: Closure<dyn FnMut() -> std::result::Result<(), JsValue>> = closure;
+            self.inner
+                .transaction_sync(closure.as_ref().unchecked_ref())
+        };
+
+        let outcome = outcome.borrow_mut().take();
+        match (result, outcome) {
+            (_, Some(Err(err))) => Err(err),
+            (Ok(_), Some(Ok(value))) => Ok(value),
+            (Err(err), _) => Err(WorkerError::from_js_err(err)),
+            (Ok(_), None) => Err(WorkerError::JsError(
+                "transaction completed without running its closure".into(),
+            )),
+        }
+    }
+
+    /// Returns a bookmark of the current state of a SQLite-backed Durable
+    /// Object, which can be passed to
+    /// [`Storage::on_next_session_restore_bookmark`] to restore it later.
+    pub async fn current_bookmark(&self) -> Result<Bookmark> {
+        let fut = self
+            .inner
+            .get_current_bookmark()
+            .map(future_from_promise)
+            .map_err(WorkerError::from_js_err)?;
+        Bookmark::from_js(fut.await.map_err(WorkerError::from_promise_err)?)
+    }
+
+    /// Returns a bookmark of the state of a SQLite-backed Durable Object at
+    /// `time`, which must be within the point-in-time recovery period of the
+    /// last 30 days.
+    ///
+    /// ```ignore
+    /// let ten_minutes_ago = Utc::now() - chrono::Duration::minutes(10);
+    /// let bookmark = storage.bookmark_for_time(ten_minutes_ago).await?;
+    /// ```
+    pub async fn bookmark_for_time(&self, time: impl Into<Date>) -> Result<Bookmark> {
+        let fut = {
+            let time = js_sys::Date::from(time.into());
+            self.inner
+                .get_bookmark_for_time(&time)
+                .map(future_from_promise)
+                .map_err(WorkerError::from_js_err)?
+        };
+        Bookmark::from_js(fut.await.map_err(WorkerError::from_promise_err)?)
+    }
+
+    /// Restores a SQLite-backed Durable Object to the state of `bookmark` the
+    /// next time it starts. Returns a bookmark of the state before the
+    /// restore, which can be used to undo it.
+    ///
+    /// The restore only happens once the object restarts, so this is usually
+    /// followed by aborting the current instance.
+    pub async fn on_next_session_restore_bookmark(&self, bookmark: &Bookmark) -> Result<Bookmark> {
+        let fut = self
+            .inner
+            .on_next_session_restore_bookmark(bookmark.as_str())
+            .map(future_from_promise)
+            .map_err(WorkerError::from_js_err)?;
+        Bookmark::from_js(fut.await.map_err(WorkerError::from_promise_err)?)
+    }
+
+    /// Runs `closure` in a transaction and returns its result.
+    ///
+    /// The transaction is committed if the closure returns `Ok`, and rolled
+    /// back if it returns `Err` or calls [`Transaction::rollback`]. The
+    /// closure runs once.
+    ///
+    /// ```ignore
+    /// let count = storage
+    ///     .transaction(|mut txn| async move {
+    ///         let count = txn.get::<usize>("count").await.unwrap_or(0) + 1;
+    ///         txn.put("count", count).await?;
+    ///         Ok(count)
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn transaction<F, Fut, T>(&self, closure: F) -> Result<T>
+    where
+        F: FnOnce(Transaction) -> Fut + 'static,
+        Fut: Future<Output = Result<T>> + 'static,
+        T: 'static, {
+        let result = SendWrapper::new(Rc::new(RefCell::new(None)));
+        let (fut, _closure) = {
+            let result = Rc::clone(&result);
+            let closure = Closure::once(move |txn: DurableObjectTransaction| {
+                future_to_promise(async move {
+                    let txn = Transaction {
+                        inner: SendWrapper::new(txn),
+                    };
+                    let outcome = closure(txn).await;
+                    // Rejecting the promise rolls the transaction back.
+                    let rejection = outcome
+                        .as_ref()
+                        .err()
+                        .map(|err| JsValue::from(err.to_string()));
+                    *result.borrow_mut() = Some(outcome);
+                    match rejection {
+                        Some(rejection) => Err(rejection),
+                        None => Ok(JsValue::UNDEFINED),
+                    }
+                })
+            });
+            let promise = self
+                .inner
+                .transaction(&closure)
+                .map_err(WorkerError::from_js_err)?;
+            (future_from_promise(promise), SendWrapper::new(closure))
+        };
+
+        let committed = fut.await;
+        let outcome = result.borrow_mut().take();
+        match (committed, outcome) {
+            (_, Some(Err(err))) => Err(err),
+            (Ok(_), Some(Ok(value))) => Ok(value),
+            (Err(err), _) => Err(WorkerError::from_promise_err(err)),
+            (Ok(_), None) => Err(WorkerError::JsError(
+                "transaction completed without running its closure".into(),
+            )),
+        }
+    }
 }
 
-#[allow(dead_code)]
-struct Transaction {
+/// A transaction started with [`Storage::transaction`]. Its operations are
+/// isolated from other storage operations and are committed together.
+pub struct Transaction {
     inner: SendWrapper<DurableObjectTransaction>,
 }
 
-#[allow(dead_code)]
 impl Transaction {
-    async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<T> {
+    /// Retrieves the value associated with the given key.
+    ///
+    /// Returns [Err] if the key does not exist.
+    pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<T> {
         let promise = self.inner.get(key).map_err(WorkerError::from_js_err)?;
         let fut = future_from_promise(promise);
 
@@ -528,7 +1446,33 @@ impl Transaction {
             .map_err(WorkerError::from_promise_err)
     }
 
-    async fn get_multiple(&self, keys: Vec<impl Deref<Target = str>>) -> Result<Map> {
+    /// Retrieves the value associated with the given key, or `None` if the key
+    /// does not exist.
+    pub async fn get_opt<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let fut = {
+            let promise = self.inner.get(key).map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        let value = fut.await.map_err(WorkerError::from_promise_err)?;
+        if value.is_undefined() {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_wasm_bindgen::from_value(value)?))
+    }
+
+    /// Retrieves the values associated with each of the provided keys. Keys
+    /// that do not exist are left out of the returned map.
+    pub async fn get_many<T: DeserializeOwned>(
+        &self, keys: Vec<impl Deref<Target = str>>,
+    ) -> Result<HashMap<String, T>> {
+        let map = self.get_multiple(keys).await?;
+        Ok(map_entries(&map)?.into_iter().collect())
+    }
+
+    /// Retrieves the values associated with each of the provided keys.
+    pub async fn get_multiple(&self, keys: Vec<impl Deref<Target = str>>) -> Result<Map> {
         let fut = {
             let keys = self
                 .inner
@@ -545,7 +1489,8 @@ impl Transaction {
         keys.dyn_into::<Map>().map_err(WorkerError::from_cast_err)
     }
 
-    async fn put<T: Serialize>(&mut self, key: &str, value: T) -> Result<()> {
+    /// Stores the value and associates it with the given key.
+    pub async fn put<T: Serialize>(&mut self, key: &str, value: T) -> Result<()> {
         let promise = self
             .inner
             .put(key, serde_wasm_bindgen::to_value(&value)?)
@@ -554,8 +1499,9 @@ impl Transaction {
         fut.await.map_err(WorkerError::from_promise_err).map(|_| ())
     }
 
-    // Each key-value pair in the serialized object will be added to the storage
-    async fn put_multiple<T: Serialize>(&mut self, values: T) -> Result<()> {
+    /// Takes a serializable struct and stores each of its keys and values to
+    /// storage.
+    pub async fn put_multiple<T: Serialize>(&mut self, values: T) -> Result<()> {
         let fut = {
             let values = serde_wasm_bindgen::to_value(&values)?;
             if !values.is_object() {
@@ -572,7 +1518,9 @@ impl Transaction {
         fut.await.map_err(WorkerError::from_promise_err).map(|_| ())
     }
 
-    async fn delete(&mut self, key: &str) -> Result<bool> {
+    /// Deletes the key and associated value. Returns true if the key existed or
+    /// false if it didn't.
+    pub async fn delete(&mut self, key: &str) -> Result<bool> {
         let fut = self
             .inner
             .delete(key)
@@ -586,7 +1534,9 @@ impl Transaction {
             .map_err(WorkerError::from_promise_err)
     }
 
-    async fn delete_multiple(&mut self, keys: Vec<impl Deref<Target = str>>) -> Result<usize> {
+    /// Deletes the provided keys and their associated values. Returns a count
+    /// of the number of key-value pairs deleted.
+    pub async fn delete_multiple(&mut self, keys: Vec<impl Deref<Target = str>>) -> Result<usize> {
         let fut = self
             .inner
             .delete_multiple(
@@ -605,7 +1555,8 @@ impl Transaction {
             .map_err(WorkerError::from_promise_err)
     }
 
-    async fn delete_all(&mut self) -> Result<()> {
+    /// Deletes all keys and associated values.
+    pub async fn delete_all(&mut self) -> Result<()> {
         let fut = self
             .inner
             .delete_all()
@@ -614,45 +1565,56 @@ impl Transaction {
         fut.await.map(|_| ()).map_err(WorkerError::from_promise_err)
     }
 
-    async fn list(&self) -> Result<Map> {
+    /// Returns all keys and values, ordered by key.
+    pub async fn list<T: DeserializeOwned>(&self) -> Result<BTreeMap<String, T>> {
         let fut = self
             .inner
             .list()
             .map(future_from_promise)
             .map_err(WorkerError::from_js_err)?;
-        fut.await
-            .and_then(|jsv| jsv.dyn_into())
-            .map_err(WorkerError::from_promise_err)
+        let map = fut
+            .await
+            .and_then(|jsv| jsv.dyn_into::<Map>())
+            .map_err(WorkerError::from_promise_err)?;
+        Ok(map_entries(&map)?.into_iter().collect())
     }
 
-    async fn list_with_options(&self, opts: ListOptions<'_>) -> Result<Map> {
+    /// Returns keys and values according to the parameters in the provided
+    /// options object, ordered by key.
+    pub async fn list_with_options<T: DeserializeOwned>(
+        &self, opts: ListOptions,
+    ) -> Result<BTreeMap<String, T>> {
         let fut = self
             .inner
             .list_with_options(serde_wasm_bindgen::to_value(&opts)?.into())
             .map(future_from_promise)
             .map_err(WorkerError::from_js_err)?;
-        fut.await
-            .and_then(|jsv| jsv.dyn_into())
-            .map_err(WorkerError::from_promise_err)
+        let map = fut
+            .await
+            .and_then(|jsv| jsv.dyn_into::<Map>())
+            .map_err(WorkerError::from_promise_err)?;
+        Ok(map_entries(&map)?.into_iter().collect())
     }
 
-    fn rollback(&mut self) -> Result<()> {
+    /// Marks the transaction for rollback. Its writes are discarded even if the
+    /// closure passed to [`Storage::transaction`] returns `Ok`.
+    pub fn rollback(&mut self) -> Result<()> {
         self.inner.rollback().map_err(WorkerError::from_js_err)
     }
 }
 
-#[derive(Default, Serialize)]
-pub struct ListOptions<'a> {
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListOptions {
     /// Key at which the list results should start, inclusive.
     #[serde(skip_serializing_if = "Option::is_none")]
-    start: Option<&'a str>,
+    start: Option<String>,
     /// Key at which the list results should end, exclusive.
     #[serde(skip_serializing_if = "Option::is_none")]
-    end: Option<&'a str>,
+    end: Option<String>,
     /// Restricts results to only include key-value pairs whose keys begin with
     /// the prefix.
     #[serde(skip_serializing_if = "Option::is_none")]
-    prefix: Option<&'a str>,
+    prefix: Option<String>,
     /// If true, return results in descending lexicographic order instead of the
     /// default ascending order.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -662,28 +1624,28 @@ pub struct ListOptions<'a> {
     limit: Option<usize>,
 }
 
-impl<'a> ListOptions<'a> {
+impl ListOptions {
     /// Create a new ListOptions struct with no options set.
     pub fn new() -> Self {
         Default::default()
     }
 
     /// Key at which the list results should start, inclusive.
-    pub fn start(mut self, val: &'a str) -> Self {
-        self.start = Some(val);
+    pub fn start(mut self, val: impl Into<String>) -> Self {
+        self.start = Some(val.into());
         self
     }
 
     /// Key at which the list results should end, exclusive.
-    pub fn end(mut self, val: &'a str) -> Self {
-        self.end = Some(val);
+    pub fn end(mut self, val: impl Into<String>) -> Self {
+        self.end = Some(val.into());
         self
     }
 
     /// Restricts results to only include key-value pairs whose keys begin with
     /// the prefix.
-    pub fn prefix(mut self, val: &'a str) -> Self {
-        self.prefix = Some(val);
+    pub fn prefix(mut self, val: impl Into<String>) -> Self {
+        self.prefix = Some(val.into());
         self
     }
 
@@ -699,6 +1661,31 @@ impl<'a> ListOptions<'a> {
         self.limit = Some(val);
         self
     }
+
+    /// Prepends `prefix` to the keys of the options, scoping them to a
+    /// [`Namespace`].
+    fn scoped(self, prefix: &str) -> Self {
+        let scope = |key: String| format!("{prefix}{key}");
+        Self {
+            start: self.start.map(scope),
+            end: self.end.map(scope),
+            prefix: Some(scope(self.prefix.unwrap_or_default())),
+            ..self
+        }
+    }
+}
+
+/// Decodes the entries of a `Map` returned by the storage API, in the order
+/// they were listed.
+fn map_entries<T: DeserializeOwned>(map: &Map) -> Result<Vec<(String, T)>> {
+    map.entries()
+        .into_iter()
+        .map(|entry| {
+            let entry: Array = entry.map_err(WorkerError::from_js_err)?.unchecked_into();
+            let key = entry.get(0).as_string().ok_or(WorkerError::JsCast)?;
+            Ok((key, serde_wasm_bindgen::from_value(entry.get(1))?))
+        })
+        .collect()
 }
 
 enum ScheduledTimeInit {
@@ -765,13 +1752,108 @@ impl From<Duration> for ScheduledTime {
     }
 }
 
+/// A point in the history of a SQLite-backed Durable Object, used for
+/// point-in-time recovery.
+///
+/// Bookmarks of the same object compare in the order of the points they refer
+/// to. They serialize as their string, so they can be stored or returned by
+/// an admin API and parsed back.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Bookmark(String);
+
+impl Bookmark {
+    /// The bookmark as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn from_js(value: JsValue) -> Result<Self> {
+        value.as_string().map(Self).ok_or(WorkerError::JsCast)
+    }
+}
+
+impl From<String> for Bookmark {
+    fn from(bookmark: String) -> Self {
+        Self(bookmark)
+    }
+}
+
+impl From<Bookmark> for String {
+    fn from(bookmark: Bookmark) -> Self {
+        bookmark.0
+    }
+}
+
+impl fmt::Display for Bookmark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Information about an invocation of [`DurableObject::alarm`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AlarmInfo {
+    /// The number of previous attempts that failed, 0 on the first attempt.
+    pub retry_count: u32,
+    /// Whether a previous attempt failed.
+    pub is_retry: bool,
+}
+
+impl AlarmInfo {
+    /// Reads the info the runtime passes to `alarm()`. Runtimes that don't pass
+    /// any get the info of a first attempt.
+    #[doc(hidden)]
+    pub fn from_js(info: JsValue) -> Result<Self> {
+        if info.is_undefined() || info.is_null() {
+            return Ok(Self::default());
+        }
+        Ok(serde_wasm_bindgen::from_value(info)?)
+    }
+}
+
+/// Options of the storage reads of a Durable Object.
 #[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetOptions {
+    /// Lets other events be delivered while the read is in progress, instead of
+    /// waiting for it as the input gate normally does.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_concurrency: Option<bool>,
+    /// Does not keep the value in the in-memory cache of the Durable Object.
+    /// Useful for values that will not be read again soon.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_cache: Option<bool>,
+}
+
+/// Options of the storage writes of a Durable Object.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PutOptions {
+    /// Lets other events be delivered while the write is in progress, instead
+    /// of waiting for it as the input gate normally does.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_concurrency: Option<bool>,
+    /// Lets the Durable Object send messages before the write is confirmed as
+    /// durable, instead of holding them back as the output gate normally does.
+    /// Use [`Storage::sync`] to wait for the write explicitly.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_unconfirmed: Option<bool>,
+    /// Does not keep the value in the in-memory cache of the Durable Object.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_cache: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GetAlarmOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub allow_concurrency: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SetAlarmOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub allow_concurrency: Option<bool>,
@@ -785,8 +1867,8 @@ pub struct SetAlarmOptions {
 ///
 /// #[durable_object]
 /// pub struct Chatroom {
-///     users: Vec<User>,
-///     messages: Vec<Message>
+///     users: RefCell<Vec<User>>,
+///     messages: RefCell<Vec<Message>>,
 ///     state: State,
 ///     env: Env, // access `Env` across requests, use inside `fetch`
 /// }
@@ -795,25 +1877,161 @@ pub struct SetAlarmOptions {
 /// impl DurableObject for Chatroom {
 ///     fn new(state: State, env: Env) -> Self {
 ///         Self {
-///             users: vec![],
-///             messages: vec![],
+///             users: RefCell::new(vec![]),
+///             messages: RefCell::new(vec![]),
 ///             state: state,
 ///             env,
 ///         }
 ///     }
 ///
-///     async fn fetch(&mut self, _req: Request) -> Result<Response> {
-///         Response::ok(&format!("{} active users.", self.users.len()))
+///     async fn fetch(&self, _req: Request) -> Result<Response> {
+///         Response::ok(&format!("{} active users.", self.users.borrow().len()))
 ///     }
 /// }
 /// ```
+///
+/// ## Concurrency
+///
+/// A Durable Object handles one event at a time, but an event that awaits lets
+/// the next one start. Events are therefore given `&self`, and the state that
+/// changes between events is kept in a `Cell` or `RefCell`. A `RefCell` borrow
+/// must not be held across an `.await`, or an interleaved event borrowing it
+/// will panic.
+///
+/// The runtime limits the interleaving with two gates:
+///
+/// - The input gate holds back new events while the object awaits its own
+///   storage, so a read followed by a write of [`Storage`] runs without other
+///   events in between. Other awaits, such as a `fetch()` to another service,
+///   open the gate.
+/// - The output gate holds back the responses and outgoing messages of the
+///   object until its storage writes are confirmed, so nothing observes a write
+///   that could still be lost.
+///
+/// Use [`State::block_concurrency_while`] to hold back events across other
+/// awaits too.
+///
+/// ## RPC
+///
+/// Every `pub async fn` of an inherent impl block marked with
+/// `#[durable_object]` is exported as a [JavaScript RPC](https://developers.cloudflare.com/durable-objects/best-practices/create-durable-object-stubs-and-send-requests/#invoke-rpc-methods)
+/// method. Arguments and return values are converted with `serde`, except for
+/// [`Body`], which is passed as a `ReadableStream`. The macro also generates a
+/// typed client named after the object with a `Stub` suffix. Like the event
+/// handlers, RPC methods take `&self` and can run concurrently with them.
+///
+/// ```ignore
+/// #[durable_object]
+/// impl Chatroom {
+///     pub async fn post(&self, message: Message) -> Result<usize> {
+///         let mut messages = self.messages.borrow_mut();
+///         messages.push(message);
+///         Ok(messages.len())
+///     }
+/// }
+///
+/// let chatroom = ChatroomStub::from(namespace.id_from_name("lobby")?.get_stub()?);
+/// let count = chatroom.post(message).await?;
+/// ```
+///
+/// RPC requires the class to be wrapped in a `DurableObject` by
+/// `worker-build`, and a compatibility date of 2024-04-03 or later.
 #[allow(async_fn_in_trait)]
 pub trait DurableObject {
     fn new(state: State, env: Env) -> Self;
 
-    async fn fetch(&mut self, req: http::Request<Body>) -> Result<http::Response<Body>>;
+    async fn fetch(&self, req: http::Request<Body>) -> Result<http::Response<Body>>;
+
+    /// Handles the alarm set with [`Storage::set_alarm`]. If it fails, the
+    /// alarm is retried with exponential backoff, and `info` tells whether
+    /// this invocation is a retry. Does nothing by default.
+    #[allow(unused_variables)]
+    async fn alarm(&self, info: AlarmInfo) -> Result<http::Response<Body>> {
+        Ok(http::Response::new(Body::empty()))
+    }
+
+    /// Handles a message received by a `WebSocket` accepted with
+    /// [`State::accept_web_socket`]. Does nothing by default.
+    #[allow(unused_variables)]
+    async fn websocket_message(
+        &self, ws: WebSocket, message: WebSocketIncomingMessage,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Handles the closing of a `WebSocket` accepted with
+    /// [`State::accept_web_socket`]. Does nothing by default.
+    #[allow(unused_variables)]
+    async fn websocket_close(
+        &self, ws: WebSocket, code: usize, reason: String, was_clean: bool,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Handles an error of a `WebSocket` accepted with
+    /// [`State::accept_web_socket`]. Does nothing by default.
+    #[allow(unused_variables)]
+    async fn websocket_error(&self, ws: WebSocket, error: WorkerError) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bookmarks_serialize_as_strings() {
+        let bookmark = Bookmark::from("0000007b-0000b26e-00001538-0c3e87bb".to_string());
+        let json = serde_json::to_string(&bookmark).unwrap();
+        assert_eq!(json, r#""0000007b-0000b26e-00001538-0c3e87bb""#);
+        assert_eq!(serde_json::from_str::<Bookmark>(&json).unwrap(), bookmark);
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_max() {
+        let options = RetryOptions {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        };
+        let delays = (1..=6)
+            .map(|attempt| options.delay(attempt))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            delays,
+            [100, 200, 400, 800, 1000, 1000].map(Duration::from_millis)
+        );
+        assert_eq!(options.delay(40), Duration::from_secs(1));
+    }
 
-    async fn alarm(&mut self) -> Result<http::Response<Body>> {
-        unimplemented!("alarm() handler not implemented")
+    #[test]
+    fn serializes_options_in_camel_case() {
+        let options = PutOptions {
+            allow_concurrency: Some(true),
+            allow_unconfirmed: Some(false),
+            no_cache: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({ "allowConcurrency": true, "allowUnconfirmed": false })
+        );
+
+        let options = SetAlarmOptions {
+            allow_unconfirmed: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({ "allowUnconfirmed": true })
+        );
+
+        let options = GetStubOptions {
+            location_hint: Some(LocationHint::Weur),
+        };
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({ "locationHint": "weur" })
+        );
     }
 }
@@ -0,0 +1,75 @@
+use js_sys::Reflect;
+use thiserror::Error;
+use wasm_bindgen::{JsCast, JsValue};
+
+/// An error thrown by the runtime for a call to a Durable Object, with the
+/// flags it sets to tell how the call failed.
+///
+/// See [Error handling](https://developers.cloudflare.com/durable-objects/best-practices/error-handling/)
+/// for when each flag is set. A Durable Object can also return this error from
+/// an event to throw it with its flags, for example to tell callers to retry.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Durable Object error: {message}")]
+pub struct DurableObjectError {
+    /// The message of the error.
+    pub message: String,
+    /// The call may succeed if retried, for example because the object was
+    /// reset or redeployed.
+    pub retryable: bool,
+    /// The object is overloaded. Retrying makes it worse, so the call should
+    /// not be retried even if it is retryable.
+    pub overloaded: bool,
+    /// The error was thrown by the code of the object rather than by the
+    /// runtime.
+    pub remote: bool,
+}
+
+impl DurableObjectError {
+    /// Whether retrying the call is recommended: the error is retryable and
+    /// the object isn't overloaded.
+    pub fn should_retry(&self) -> bool {
+        self.retryable && !self.overloaded
+    }
+
+    /// Converts the error into a JavaScript `Error` carrying its flags.
+    pub(crate) fn to_js(&self) -> JsValue {
+        let err = js_sys::Error::new(&self.message);
+        for (name, value) in [
+            ("retryable", self.retryable),
+            ("overloaded", self.overloaded),
+            ("remote", self.remote),
+        ] {
+            let _ = Reflect::set(&err, &JsValue::from_str(name), &JsValue::from_bool(value));
+        }
+        err.into()
+    }
+
+    /// Reads the flags of `err`, or returns `None` if it has none of them.
+    pub(crate) fn from_js(err: &JsValue) -> Option<Self> {
+        if !err.is_object() {
+            return None;
+        }
+
+        let flag = |name: &str| {
+            Reflect::get(err, &JsValue::from_str(name))
+                .ok()
+                .and_then(|value| value.as_bool())
+        };
+        let (retryable, overloaded, remote) =
+            (flag("retryable"), flag("overloaded"), flag("remote"));
+        if retryable.is_none() && overloaded.is_none() && remote.is_none() {
+            return None;
+        }
+
+        let message = err
+            .dyn_ref::<js_sys::Error>()
+            .map(|e| String::from(e.message()))
+            .unwrap_or_else(|| format!("{err:?}"));
+        Some(Self {
+            message,
+            retryable: retryable.unwrap_or_default(),
+            overloaded: overloaded.unwrap_or_default(),
+            remote: remote.unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
+
+use futures_util::{Stream, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::{ListOptions, Storage};
+use crate::result::Result;
+
+/// A typed view of the keys of a [`Storage`] starting with a prefix.
+///
+/// Keys passed to and returned by a namespace are relative to its prefix, so
+/// several kinds of records can share the storage of a Durable Object without
+/// their keys colliding.
+///
+/// ```ignore
+/// let mut users = self.state.storage().namespace::<User>("user:");
+/// users.put("ada", &user).await?;
+///
+/// // Stored under the key `user:ada`.
+/// let user = users.get("ada").await?;
+/// ```
+pub struct Namespace<T> {
+    storage: Storage,
+    prefix: String,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T> Namespace<T> {
+    /// Creates a view of the keys of `storage` starting with `prefix`.
+    pub fn new(storage: Storage, prefix: impl Into<String>) -> Self {
+        Self {
+            storage,
+            prefix: prefix.into(),
+            _value: PhantomData,
+        }
+    }
+
+    /// The prefix of the keys of the namespace.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{key}", self.prefix)
+    }
+
+    fn strip(&self, key: String) -> String {
+        key.strip_prefix(self.prefix.as_str())
+            .map(str::to_string)
+            .unwrap_or(key)
+    }
+}
+
+impl<T: DeserializeOwned> Namespace<T> {
+    /// Retrieves the value associated with the given key, or `None` if the key
+    /// does not exist.
+    pub async fn get(&self, key: &str) -> Result<Option<T>> {
+        self.storage.get_opt(&self.key(key)).await
+    }
+
+    /// Retrieves the values associated with each of the provided keys. Keys
+    /// that do not exist are left out of the returned map.
+    pub async fn get_many(&self, keys: &[&str]) -> Result<HashMap<String, T>> {
+        let keys = keys.iter().map(|key| self.key(key)).collect::<Vec<_>>();
+        let values = self.storage.get_many(keys).await?;
+        Ok(values
+            .into_iter()
+            .map(|(key, value)| (self.strip(key), value))
+            .collect())
+    }
+
+    /// Returns all keys and values of the namespace, ordered by key.
+    pub async fn list(&self) -> Result<BTreeMap<String, T>> {
+        self.list_with_options(ListOptions::new()).await
+    }
+
+    /// Returns keys and values of the namespace according to the parameters in
+    /// the provided options object, ordered by key. The keys of the options are
+    /// relative to the prefix of the namespace.
+    pub async fn list_with_options(&self, opts: ListOptions) -> Result<BTreeMap<String, T>> {
+        let values = self
+            .storage
+            .list_with_options(opts.scoped(&self.prefix))
+            .await?;
+        Ok(values
+            .into_iter()
+            .map(|(key, value)| (self.strip(key), value))
+            .collect())
+    }
+
+    /// Returns a stream of the keys and values of the namespace, fetched in
+    /// pages of `page_size` keys. See [`Storage::list_stream`].
+    pub fn list_stream(
+        &self, opts: ListOptions, page_size: usize,
+    ) -> impl Stream<Item = Result<(String, T)>> + '_ {
+        self.storage
+            .list_stream(opts.scoped(&self.prefix), page_size)
+            .map_ok(|(key, value)| (self.strip(key), value))
+    }
+}
+
+impl<T: Serialize> Namespace<T> {
+    /// Stores the value and associates it with the given key.
+    pub async fn put(&mut self, key: &str, value: &T) -> Result<()> {
+        let key = self.key(key);
+        self.storage.put(&key, value).await
+    }
+
+    /// Deletes the key and associated value. Returns true if the key existed or
+    /// false if it didn't.
+    pub async fn delete(&mut self, key: &str) -> Result<bool> {
+        let key = self.key(key);
+        self.storage.delete(&key).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_list_options_to_prefix() {
+        let opts = ListOptions::new().start("b").end("d").scoped("user:");
+        assert_eq!(
+            serde_json::to_value(&opts).unwrap(),
+            serde_json::json!({ "start": "user:b", "end": "user:d", "prefix": "user:" })
+        );
+
+        let opts = ListOptions::new().prefix("a").limit(2).scoped("user:");
+        assert_eq!(
+            serde_json::to_value(&opts).unwrap(),
+            serde_json::json!({ "prefix": "user:a", "limit": 2 })
+        );
+    }
+}
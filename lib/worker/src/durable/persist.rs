@@ -0,0 +1,253 @@
+use std::cell::{Cell, OnceCell, RefCell};
+use std::marker::PhantomData;
+
+use js_sys::{Object, Reflect};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::JsValue;
+
+use super::Storage;
+use crate::error::WorkerError;
+use crate::futures::future_from_promise;
+use crate::result::Result;
+
+/// How a [`Persisted`] field is encoded in storage.
+pub trait Codec {
+    fn encode<T: Serialize>(value: &T) -> Result<JsValue>;
+
+    fn decode<T: DeserializeOwned>(value: JsValue) -> Result<T>;
+}
+
+/// Stores values as structured clones, the same way [`Storage::put`] does.
+pub struct StructuredCloneCodec;
+
+impl Codec for StructuredCloneCodec {
+    fn encode<T: Serialize>(value: &T) -> Result<JsValue> {
+        Ok(serde_wasm_bindgen::to_value(value)?)
+    }
+
+    fn decode<T: DeserializeOwned>(value: JsValue) -> Result<T> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+/// Stores values as JSON strings, for types that don't map onto structured
+/// clones, such as maps with non-string keys.
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    fn encode<T: Serialize>(value: &T) -> Result<JsValue> {
+        Ok(JsValue::from(serde_json::to_string(value)?))
+    }
+
+    fn decode<T: DeserializeOwned>(value: JsValue) -> Result<T> {
+        let json = value.as_string().ok_or(WorkerError::JsCast)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+/// A field of a Durable Object kept in its storage.
+///
+/// Fields marked with `#[persist]` in a `#[durable_object]` struct are loaded
+/// from storage the first time they are accessed, and the fields that changed
+/// are written with a single `put_multiple()` at the end of every event the
+/// object handles. Since the output gate holds the response back until the
+/// write is confirmed, a change is durable once the response is seen. If the
+/// write fails, the fields stay marked as changed and are written again at
+/// the end of the next event.
+///
+/// The fields are written even when the event returns an error, like the
+/// writes it made with [`Storage`] directly, so changes made before the error
+/// are kept.
+///
+/// The field is stored under its name, or under the key given with
+/// `#[persist(key = "...")]`, and encoded with the codec `C`.
+///
+/// ```ignore
+/// #[durable_object]
+/// pub struct Chatroom {
+///     #[persist]
+///     messages: Persisted<Vec<Message>>,
+///     #[persist(key = "bans")]
+///     banned: Persisted<HashMap<UserId, Ban>, JsonCodec>,
+///     state: State,
+/// }
+///
+/// async fn fetch(&self, req: Request<Body>) -> Result<Response<Body>> {
+///     let count = self.messages.update(|messages| {
+///         messages.push(message);
+///         messages.len()
+///     }).await?;
+///     // ...
+/// }
+/// ```
+///
+/// Fields are bound to storage after `DurableObject::new` returns, so they
+/// can't be read from there, only set.
+pub struct Persisted<T, C = StructuredCloneCodec> {
+    binding: OnceCell<(&'static str, Storage)>,
+    value: RefCell<T>,
+    loaded: Cell<bool>,
+    dirty: Cell<bool>,
+    _codec: PhantomData<fn() -> C>,
+}
+
+impl<T, C> Persisted<T, C> {
+    /// Creates a field holding `initial` until a stored value is loaded, or
+    /// if there is none.
+    pub fn new(initial: T) -> Self {
+        Self {
+            binding: OnceCell::new(),
+            value: RefCell::new(initial),
+            loaded: Cell::new(false),
+            dirty: Cell::new(false),
+            _codec: PhantomData,
+        }
+    }
+
+    /// Replaces the value without loading the stored one.
+    pub fn set(&self, value: T) {
+        *self.value.borrow_mut() = value;
+        self.loaded.set(true);
+        self.dirty.set(true);
+    }
+
+    /// Whether the value changed since it was last written to storage.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    #[doc(hidden)]
+    pub fn bind(&self, key: &'static str, storage: &Storage) {
+        let _ = self.binding.set((key, storage.clone()));
+    }
+
+    fn binding(&self) -> Result<&(&'static str, Storage)> {
+        self.binding.get().ok_or(WorkerError::UnboundPersistedField)
+    }
+}
+
+impl<T: Default, C> Default for Persisted<T, C> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: DeserializeOwned, C: Codec> Persisted<T, C> {
+    /// Returns a copy of the value, loading it first if needed.
+    pub async fn get(&self) -> Result<T>
+    where
+        T: Clone, {
+        self.read(T::clone).await
+    }
+
+    /// Calls `f` with the value, loading it first if needed.
+    pub async fn read<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R> {
+        self.load().await?;
+        Ok(f(&self.value.borrow()))
+    }
+
+    /// Calls `f` to change the value, loading it first if needed, and marks
+    /// the field to be written at the end of the event.
+    pub async fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R> {
+        self.load().await?;
+        let result = f(&mut self.value.borrow_mut());
+        self.dirty.set(true);
+        Ok(result)
+    }
+
+    async fn load(&self) -> Result<()> {
+        if self.loaded.get() {
+            return Ok(());
+        }
+
+        let (key, storage) = self.binding()?;
+        let fut = {
+            let promise = storage.inner.get(key).map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+        let value = fut.await.map_err(WorkerError::from_promise_err)?;
+
+        // Another event may have loaded or set the value while this one was
+        // waiting for storage.
+        if !self.loaded.get() {
+            if !value.is_undefined() {
+                *self.value.borrow_mut() = C::decode(value)?;
+            }
+            self.loaded.set(true);
+        }
+        Ok(())
+    }
+}
+
+impl<T: Serialize, C: Codec> Persisted<T, C> {
+    #[doc(hidden)]
+    pub fn stage<'a>(&'a self, batch: &mut PersistBatch<'a>) -> Result<()> {
+        if !self.dirty.get() {
+            return Ok(());
+        }
+
+        let (key, storage) = self.binding()?;
+        let value = C::encode(&*self.value.borrow())?;
+        Reflect::set(&batch.entries, &JsValue::from_str(key), &value)
+            .map_err(WorkerError::from_js_err)?;
+        batch.storage.get_or_insert_with(|| storage.clone());
+        batch.staged.push(&self.dirty);
+        self.dirty.set(false);
+        Ok(())
+    }
+}
+
+/// Implemented by `#[durable_object]` for the `#[persist]` fields of a
+/// Durable Object.
+#[doc(hidden)]
+pub trait PersistedFields {
+    /// Binds every `#[persist]` field to its key in `storage`.
+    fn bind(&self, storage: &Storage);
+
+    /// Adds the fields that changed to `batch`.
+    fn stage<'a>(&'a self, batch: &mut PersistBatch<'a>) -> Result<()>;
+}
+
+/// The changed fields of a Durable Object, written together.
+#[doc(hidden)]
+pub struct PersistBatch<'a> {
+    storage: Option<Storage>,
+    entries: Object,
+    /// The dirty flags of the staged fields, set again if the write fails.
+    staged: Vec<&'a Cell<bool>>,
+}
+
+/// Writes the `#[persist]` fields of `object` that changed.
+#[doc(hidden)]
+pub async fn flush_persisted<T: PersistedFields>(object: &T) -> Result<()> {
+    let mut batch = PersistBatch {
+        storage: None,
+        entries: Object::new(),
+        staged: Vec::new(),
+    };
+    let staged = object.stage(&mut batch);
+
+    let result = match (staged, &batch.storage) {
+        (Err(err), _) => Err(err),
+        (Ok(()), None) => return Ok(()),
+        (Ok(()), Some(storage)) => {
+            let fut = storage
+                .inner
+                .put_multiple(batch.entries.into())
+                .map(future_from_promise)
+                .map_err(WorkerError::from_js_err);
+            match fut {
+                Ok(fut) => fut.await.map_err(WorkerError::from_promise_err).map(|_| ()),
+                Err(err) => Err(err),
+            }
+        },
+    };
+
+    if result.is_err() {
+        for dirty in batch.staged {
+            dirty.set(true);
+        }
+    }
+    result
+}
@@ -0,0 +1,72 @@
+//! Ready-made Durable Objects for coordinating Workers: a lock, a counting
+//! semaphore, an atomic counter and a leader lease.
+//!
+//! The module is enabled by the `durable-primitives` feature, which exports
+//! the objects from the Worker.
+//!
+//! Each object is used through the typed client `#[durable_object]` generates
+//! for it, such as [`DurableLockStub`]. The objects still have to be bound and
+//! migrated like any other Durable Object, under the name of their class:
+//!
+//! ```toml
+//! [durable_objects]
+//! bindings = [{ name = "LOCKS", class_name = "DurableLock" }]
+//!
+//! [[migrations]]
+//! tag = "v2"
+//! new_classes = ["DurableLock"]
+//! ```
+//!
+//! ```ignore
+//! let lock = DurableLockStub::from(env.durable_object("LOCKS")?.get_by_name("invoices")?);
+//! if lock.acquire(worker_id, Duration::from_secs(30)).await? {
+//!     // ...
+//!     lock.release(worker_id).await?;
+//! }
+//! ```
+//!
+//! Holders of a lock, semaphore or lease give a time to live when acquiring
+//! it, so that it is released if they never do. The objects treat expired
+//! holders as gone right away, and remove them from storage when their alarm
+//! fires.
+
+mod counter;
+mod lease;
+mod lock;
+mod semaphore;
+
+use std::time::Duration;
+
+pub use counter::{DurableCounter, DurableCounterStub};
+use js_sys::Number;
+pub use lease::{LeaderLease, LeaderLeaseStub, Lease};
+pub use lock::{DurableLock, DurableLockStub, LockHolder};
+pub use semaphore::{DurableSemaphore, DurableSemaphoreStub, Permit};
+
+use super::ScheduledTime;
+use crate::body::Body;
+use crate::date::Date;
+
+/// The current time in milliseconds since epoch.
+fn now() -> u64 {
+    Date::now().as_millis()
+}
+
+/// The time `ttl` from now in milliseconds since epoch.
+fn expiry(ttl: Duration) -> u64 {
+    now().saturating_add(ttl.as_millis() as u64)
+}
+
+/// The alarm time of the deadline `at`, in milliseconds since epoch.
+fn scheduled(at: u64) -> ScheduledTime {
+    ScheduledTime::new(js_sys::Date::new(&Number::from(at as f64)))
+}
+
+/// The response of the primitives to `fetch()`, since they are only used
+/// through RPC.
+fn rpc_only() -> http::Response<Body> {
+    http::Response::builder()
+        .status(http::StatusCode::NOT_FOUND)
+        .body("This Durable Object is only used through RPC".into())
+        .unwrap()
+}
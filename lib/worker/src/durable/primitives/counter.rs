@@ -0,0 +1,66 @@
+use super::rpc_only;
+use crate::body::Body;
+use crate::durable::{DurableObject, Persisted, State};
+use crate::durable_object;
+use crate::env::Env;
+use crate::error::WorkerError;
+use crate::result::Result;
+
+/// A counter whose updates are atomic.
+#[durable_object]
+pub struct DurableCounter {
+    #[persist]
+    value: Persisted<i64>,
+}
+
+#[durable_object]
+impl DurableObject for DurableCounter {
+    fn new(_: State, _: Env) -> Self {
+        Self {
+            value: Persisted::default(),
+        }
+    }
+
+    async fn fetch(&self, _: http::Request<Body>) -> Result<http::Response<Body>> {
+        Ok(rpc_only())
+    }
+}
+
+#[durable_object]
+impl DurableCounter {
+    /// The value of the counter, 0 until it is first changed.
+    pub async fn get(&self) -> Result<i64> {
+        self.value.get().await
+    }
+
+    /// Adds `delta` to the counter and returns the new value. Fails with
+    /// [`WorkerError::CounterOverflow`], leaving the counter unchanged, if the
+    /// new value doesn't fit an `i64`.
+    pub async fn add(&self, delta: i64) -> Result<i64> {
+        let value = self.value.get().await?;
+        let new = value
+            .checked_add(delta)
+            .ok_or(WorkerError::CounterOverflow(delta))?;
+        if new != value {
+            self.value.set(new);
+        }
+        Ok(new)
+    }
+
+    /// Sets the counter to `value` and returns the previous value.
+    pub async fn set(&self, value: i64) -> Result<i64> {
+        self.value
+            .update(|current| std::mem::replace(current, value))
+            .await
+    }
+
+    /// Sets the counter to `new` if it is `expected`. Returns whether it was
+    /// set.
+    pub async fn compare_and_swap(&self, expected: i64, new: i64) -> Result<bool> {
+        let swapped = self.value.read(|value| *value == expected).await?;
+        if swapped && new != expected {
+            self.value.set(new);
+        }
+        Ok(swapped)
+    }
+}
@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{expiry, now, rpc_only, scheduled};
+use crate::body::Body;
+use crate::durable::{AlarmInfo, DurableObject, Persisted, State, Timers};
+use crate::durable_object;
+use crate::env::Env;
+use crate::result::Result;
+
+/// The name of the timer of the expiry of the lease.
+const TIMER: &str = "lease";
+
+/// The lease of the leader elected by a [`LeaderLease`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lease {
+    pub leader: String,
+    /// The number of the lease, which grows every time a new leader is
+    /// elected. It can be used as a fencing token, so that resources reject
+    /// the requests of a previous leader.
+    pub term: u64,
+    /// When the lease expires, in milliseconds since epoch.
+    pub expires_at: u64,
+}
+
+impl Lease {
+    /// Whether `candidate` is the leader.
+    pub fn is_held_by(&self, candidate: &str) -> bool {
+        self.leader == candidate
+    }
+}
+
+/// Elects a single leader among candidates, for as long as it renews its
+/// lease.
+#[durable_object]
+pub struct LeaderLease {
+    #[persist]
+    lease: Persisted<Option<Lease>>,
+    /// The term of the last lease, kept after the lease expires.
+    #[persist]
+    term: Persisted<u64>,
+    state: State,
+}
+
+#[durable_object]
+impl DurableObject for LeaderLease {
+    fn new(state: State, _: Env) -> Self {
+        Self {
+            lease: Persisted::default(),
+            term: Persisted::default(),
+            state,
+        }
+    }
+
+    async fn fetch(&self, _: http::Request<Body>) -> Result<http::Response<Body>> {
+        Ok(rpc_only())
+    }
+
+    async fn alarm(&self, _: AlarmInfo) -> Result<http::Response<Body>> {
+        Timers::new(self.state.storage()).take_due().await?;
+        let now = now();
+        let expired = self
+            .lease
+            .read(|lease| lease.as_ref().is_some_and(|lease| lease.expires_at <= now))
+            .await?;
+        if expired {
+            self.lease.set(None);
+        }
+        Ok(http::Response::new(Body::empty()))
+    }
+}
+
+#[durable_object]
+impl LeaderLease {
+    /// Makes `candidate` the leader until `ttl` from now if there is no
+    /// leader, or renews its lease if it already is. Returns the current
+    /// lease, which is held by another candidate if `candidate` lost.
+    pub async fn campaign(&self, candidate: String, ttl: Duration) -> Result<Lease> {
+        let now = now();
+        let expires_at = expiry(ttl);
+        let term = self.term.get().await?;
+        let current = self
+            .lease
+            .read(|lease| lease.clone().filter(|lease| lease.expires_at > now))
+            .await?;
+        // Both fields are loaded, so nothing awaits between reading and
+        // writing the lease.
+        let lease = match current {
+            Some(lease) if !lease.is_held_by(&candidate) => return Ok(lease),
+            Some(lease) => Lease {
+                expires_at,
+                ..lease
+            },
+            None => Lease {
+                leader: candidate,
+                term: term + 1,
+                expires_at,
+            },
+        };
+        self.lease.set(Some(lease.clone()));
+        if lease.term > term {
+            self.term.set(lease.term);
+        }
+
+        let mut timers = Timers::new(self.state.storage());
+        timers.set(TIMER, scheduled(expires_at)).await?;
+        Ok(lease)
+    }
+
+    /// Ends the lease of `candidate` early. Returns false if it isn't the
+    /// leader.
+    pub async fn resign(&self, candidate: String) -> Result<bool> {
+        let now = now();
+        let resigned = self
+            .lease
+            .read(|lease| {
+                lease
+                    .as_ref()
+                    .is_some_and(|lease| lease.expires_at > now && lease.is_held_by(&candidate))
+            })
+            .await?;
+
+        if resigned {
+            self.lease.set(None);
+            Timers::new(self.state.storage()).cancel(TIMER).await?;
+        }
+        Ok(resigned)
+    }
+
+    /// The lease of the current leader, if any.
+    pub async fn leader(&self) -> Result<Option<Lease>> {
+        let now = now();
+        self.lease
+            .read(|lease| lease.clone().filter(|lease| lease.expires_at > now))
+            .await
+    }
+}
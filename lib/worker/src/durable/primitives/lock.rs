@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{expiry, now, rpc_only, scheduled};
+use crate::body::Body;
+use crate::durable::{AlarmInfo, DurableObject, Persisted, State, Timers};
+use crate::durable_object;
+use crate::env::Env;
+use crate::result::Result;
+
+/// The name of the timer of the expiry of the lock.
+const TIMER: &str = "lock";
+
+/// The current holder of a [`DurableLock`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub owner: String,
+    /// When the lock expires, in milliseconds since epoch.
+    pub expires_at: u64,
+}
+
+/// A lock held by at most one owner at a time.
+#[durable_object]
+pub struct DurableLock {
+    #[persist]
+    holder: Persisted<Option<LockHolder>>,
+    state: State,
+}
+
+#[durable_object]
+impl DurableObject for DurableLock {
+    fn new(state: State, _: Env) -> Self {
+        Self {
+            holder: Persisted::default(),
+            state,
+        }
+    }
+
+    async fn fetch(&self, _: http::Request<Body>) -> Result<http::Response<Body>> {
+        Ok(rpc_only())
+    }
+
+    async fn alarm(&self, _: AlarmInfo) -> Result<http::Response<Body>> {
+        Timers::new(self.state.storage()).take_due().await?;
+        let now = now();
+        let expired = self
+            .holder
+            .read(|holder| {
+                holder
+                    .as_ref()
+                    .is_some_and(|holder| holder.expires_at <= now)
+            })
+            .await?;
+        if expired {
+            self.holder.set(None);
+        }
+        Ok(http::Response::new(Body::empty()))
+    }
+}
+
+#[durable_object]
+impl DurableLock {
+    /// Acquires the lock for `owner` until `ttl` from now, or extends it if
+    /// `owner` already holds it. Returns false if another owner holds it.
+    pub async fn acquire(&self, owner: String, ttl: Duration) -> Result<bool> {
+        let now = now();
+        let expires_at = expiry(ttl);
+        let acquired = self
+            .holder
+            .read(|holder| {
+                !matches!(holder, Some(holder) if holder.owner != owner && holder.expires_at > now)
+            })
+            .await?;
+
+        if acquired {
+            self.holder.set(Some(LockHolder { owner, expires_at }));
+            let mut timers = Timers::new(self.state.storage());
+            timers.set(TIMER, scheduled(expires_at)).await?;
+        }
+        Ok(acquired)
+    }
+
+    /// Releases the lock if `owner` holds it. Returns false if it doesn't.
+    pub async fn release(&self, owner: String) -> Result<bool> {
+        let now = now();
+        let released = self
+            .holder
+            .read(|holder| {
+                holder
+                    .as_ref()
+                    .is_some_and(|holder| holder.owner == owner && holder.expires_at > now)
+            })
+            .await?;
+
+        if released {
+            self.holder.set(None);
+            Timers::new(self.state.storage()).cancel(TIMER).await?;
+        }
+        Ok(released)
+    }
+
+    /// The current holder of the lock, if any.
+    pub async fn holder(&self) -> Result<Option<LockHolder>> {
+        let now = now();
+        self.holder
+            .read(|holder| holder.clone().filter(|holder| holder.expires_at > now))
+            .await
+    }
+}
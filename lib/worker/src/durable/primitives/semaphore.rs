@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{expiry, now, rpc_only, scheduled};
+use crate::body::Body;
+use crate::durable::{AlarmInfo, DurableObject, Persisted, State, Timers};
+use crate::durable_object;
+use crate::env::Env;
+use crate::result::Result;
+
+/// A permit of a [`DurableSemaphore`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Permit {
+    pub holder: String,
+    /// When the permit expires, in milliseconds since epoch.
+    pub expires_at: u64,
+}
+
+/// A counting semaphore, handing out a limited number of permits.
+///
+/// The limit is passed to each call of [`DurableSemaphore::acquire`], so it
+/// can be changed without migrating the object. Lowering it doesn't revoke
+/// permits already held.
+#[durable_object]
+pub struct DurableSemaphore {
+    /// The expiry of the permit of each holder.
+    #[persist]
+    permits: Persisted<BTreeMap<String, u64>>,
+    state: State,
+}
+
+#[durable_object]
+impl DurableObject for DurableSemaphore {
+    fn new(state: State, _: Env) -> Self {
+        Self {
+            permits: Persisted::default(),
+            state,
+        }
+    }
+
+    async fn fetch(&self, _: http::Request<Body>) -> Result<http::Response<Body>> {
+        Ok(rpc_only())
+    }
+
+    async fn alarm(&self, _: AlarmInfo) -> Result<http::Response<Body>> {
+        Timers::new(self.state.storage()).take_due().await?;
+        let now = now();
+        let expired = self
+            .permits
+            .read(|permits| permits.values().any(|expires_at| *expires_at <= now))
+            .await?;
+        if expired {
+            self.permits
+                .update(|permits| permits.retain(|_, expires_at| *expires_at > now))
+                .await?;
+        }
+        Ok(http::Response::new(Body::empty()))
+    }
+}
+
+#[durable_object]
+impl DurableSemaphore {
+    /// Acquires a permit for `holder` until `ttl` from now if fewer than
+    /// `limit` permits are held, or extends it if `holder` already has one.
+    /// Returns false if all permits are held.
+    pub async fn acquire(&self, holder: String, limit: u32, ttl: Duration) -> Result<bool> {
+        let now = now();
+        let expires_at = expiry(ttl);
+        let acquired = self
+            .permits
+            .read(|permits| {
+                let held = permits
+                    .get(&holder)
+                    .is_some_and(|expires_at| *expires_at > now);
+                held || permits
+                    .values()
+                    .filter(|expires_at| **expires_at > now)
+                    .count()
+                    < limit as usize
+            })
+            .await?;
+
+        if acquired {
+            self.permits
+                .update(|permits| {
+                    permits.retain(|_, expires_at| *expires_at > now);
+                    permits.insert(holder.clone(), expires_at);
+                })
+                .await?;
+            let mut timers = Timers::new(self.state.storage());
+            timers.set(&holder, scheduled(expires_at)).await?;
+        }
+        Ok(acquired)
+    }
+
+    /// Releases the permit of `holder`. Returns false if it has none.
+    pub async fn release(&self, holder: String) -> Result<bool> {
+        let now = now();
+        let permit = self
+            .permits
+            .read(|permits| permits.get(&holder).copied())
+            .await?;
+        if permit.is_some() {
+            self.permits
+                .update(|permits| permits.remove(&holder))
+                .await?;
+        }
+
+        Timers::new(self.state.storage()).cancel(&holder).await?;
+        Ok(permit.is_some_and(|expires_at| expires_at > now))
+    }
+
+    /// The permits currently held, ordered by holder.
+    pub async fn permits(&self) -> Result<Vec<Permit>> {
+        let now = now();
+        self.permits
+            .read(|permits| {
+                permits
+                    .iter()
+                    .filter(|(_, expires_at)| **expires_at > now)
+                    .map(|(holder, expires_at)| Permit {
+                        holder: holder.clone(),
+                        expires_at: *expires_at,
+                    })
+                    .collect()
+            })
+            .await
+    }
+}
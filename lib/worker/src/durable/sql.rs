@@ -0,0 +1,245 @@
+//! The storage APIs of [SQLite-backed Durable Objects](https://developers.cloudflare.com/durable-objects/api/storage-api/#sql-api),
+//! which are enabled for a class with `new_sqlite_classes` in the migrations
+//! of your wrangler.toml file.
+
+use std::marker::PhantomData;
+
+use betterworker_sys::{
+    SqlStorage as SqlStorageSys, SqlStorageCursor, SyncKvStorage as SyncKvStorageSys,
+};
+use js_sys::{Array, Uint8Array};
+use send_wrapper::SendWrapper;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::{JsCast, JsValue};
+
+use super::ListOptions;
+use crate::error::WorkerError;
+use crate::result::Result;
+
+/// A value bound to a parameter of a SQL query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    Null,
+    /// An integer. Integers are passed to the runtime as JavaScript numbers,
+    /// so they lose precision beyond 2<sup>53</sup>.
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl From<SqlValue> for JsValue {
+    fn from(value: SqlValue) -> Self {
+        match value {
+            SqlValue::Null => JsValue::NULL,
+            SqlValue::Integer(value) => JsValue::from_f64(value as f64),
+            SqlValue::Real(value) => JsValue::from_f64(value),
+            SqlValue::Text(value) => JsValue::from_str(&value),
+            SqlValue::Blob(value) => Uint8Array::from(value.as_slice()).buffer().into(),
+        }
+    }
+}
+
+macro_rules! sql_value_from {
+    ($variant:ident: $($ty:ty),*) => {
+        $(
+            impl From<$ty> for SqlValue {
+                fn from(value: $ty) -> Self {
+                    Self::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+sql_value_from!(Integer: i8, i16, i32, i64, u8, u16, u32);
+sql_value_from!(Real: f32, f64);
+sql_value_from!(Text: String, &str);
+sql_value_from!(Blob: Vec<u8>, &[u8]);
+
+impl From<bool> for SqlValue {
+    fn from(value: bool) -> Self {
+        Self::Integer(value.into())
+    }
+}
+
+impl<T: Into<SqlValue>> From<Option<T>> for SqlValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+/// The SQL database of a SQLite-backed Durable Object.
+///
+/// ```ignore
+/// #[derive(Deserialize)]
+/// struct User {
+///     id: i64,
+///     name: String,
+/// }
+///
+/// let sql = self.state.storage().sql();
+/// sql.exec::<()>("CREATE TABLE IF NOT EXISTS users (id INTEGER PRIMARY KEY, name TEXT)", &[])?;
+/// sql.exec::<()>("INSERT INTO users (name) VALUES (?)", &["Ada".into()])?;
+///
+/// for user in sql.exec::<User>("SELECT * FROM users", &[])? {
+///     let user = user?;
+/// }
+/// ```
+pub struct SqlStorage(SendWrapper<SqlStorageSys>);
+
+impl SqlStorage {
+    pub(crate) fn new(inner: SqlStorageSys) -> Self {
+        Self(SendWrapper::new(inner))
+    }
+
+    /// Runs a query, binding `params` to its `?` placeholders, and returns a
+    /// cursor over the resulting rows deserialized into `T`. The query runs
+    /// immediately, even if the cursor is not read.
+    pub fn exec<T: DeserializeOwned>(
+        &self, query: &str, params: &[SqlValue],
+    ) -> Result<SqlCursor<T>> {
+        let params = params.iter().cloned().map(JsValue::from).collect::<Array>();
+        let cursor = self
+            .0
+            .exec(query, &params)
+            .map_err(WorkerError::from_js_err)?;
+
+        Ok(SqlCursor {
+            inner: SendWrapper::new(cursor),
+            _row: PhantomData,
+        })
+    }
+
+    /// The size of the database in bytes.
+    pub fn database_size(&self) -> u64 {
+        self.0.database_size() as u64
+    }
+}
+
+/// The rows returned by [`SqlStorage::exec`], deserialized into `T` with
+/// column names as field names.
+pub struct SqlCursor<T> {
+    inner: SendWrapper<SqlStorageCursor>,
+    _row: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> SqlCursor<T> {
+    /// Returns the only row. Fails if the query returned no row or more than
+    /// one.
+    pub fn one(self) -> Result<T> {
+        let row = self.inner.one().map_err(WorkerError::from_js_err)?;
+        Ok(serde_wasm_bindgen::from_value(row)?)
+    }
+
+    /// Returns the remaining rows as arrays of values, deserialized into `R`,
+    /// such as a tuple. This is useful when several columns have the same
+    /// name.
+    pub fn raw<R: DeserializeOwned>(self) -> Result<SqlRawCursor<R>> {
+        let iter = self.inner.raw().map_err(WorkerError::from_js_err)?;
+        Ok(SqlRawCursor {
+            inner: SendWrapper::new(iter),
+            _row: PhantomData,
+        })
+    }
+
+    /// The names of the columns, in the order they appear in the rows.
+    pub fn column_names(&self) -> Vec<String> {
+        self.inner
+            .column_names()
+            .iter()
+            .filter_map(|name| name.as_string())
+            .collect()
+    }
+
+    /// The number of rows read so far. This only includes the rows of the
+    /// cursor that were already read.
+    pub fn rows_read(&self) -> u64 {
+        self.inner.rows_read() as u64
+    }
+
+    /// The number of rows written so far.
+    pub fn rows_written(&self) -> u64 {
+        self.inner.rows_written() as u64
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for SqlCursor<T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_row(&self.inner)
+    }
+}
+
+/// The rows of a [`SqlCursor`] as arrays of values.
+pub struct SqlRawCursor<R> {
+    inner: SendWrapper<js_sys::Iterator>,
+    _row: PhantomData<R>,
+}
+
+impl<R: DeserializeOwned> Iterator for SqlRawCursor<R> {
+    type Item = Result<R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_row(&self.inner)
+    }
+}
+
+fn next_row<T: DeserializeOwned>(iter: &js_sys::Iterator) -> Option<Result<T>> {
+    let next = match iter.next() {
+        Ok(next) => next,
+        Err(err) => return Some(Err(WorkerError::from_js_err(err))),
+    };
+    if next.done() {
+        return None;
+    }
+
+    Some(serde_wasm_bindgen::from_value(next.value()).map_err(WorkerError::from))
+}
+
+/// The synchronous key-value API of a SQLite-backed Durable Object. Values are
+/// stored in the same database as the SQL tables.
+pub struct SyncKvStorage(SendWrapper<SyncKvStorageSys>);
+
+impl SyncKvStorage {
+    pub(crate) fn new(inner: SyncKvStorageSys) -> Self {
+        Self(SendWrapper::new(inner))
+    }
+
+    /// Retrieves the value associated with the given key, or `None` if the key
+    /// does not exist.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let value = self.0.get(key).map_err(WorkerError::from_js_err)?;
+        if value.is_undefined() {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_wasm_bindgen::from_value(value)?))
+    }
+
+    /// Stores the value and associates it with the given key.
+    pub fn put<T: Serialize>(&mut self, key: &str, value: T) -> Result<()> {
+        self.0
+            .put(key, serde_wasm_bindgen::to_value(&value)?)
+            .map_err(WorkerError::from_js_err)
+    }
+
+    /// Deletes the key and associated value. Returns true if the key existed or
+    /// false if it didn't.
+    pub fn delete(&mut self, key: &str) -> Result<bool> {
+        self.0.delete(key).map_err(WorkerError::from_js_err)
+    }
+
+    /// Returns the keys and values matching the options in ascending
+    /// lexicographic order of the keys, unless reversed.
+    pub fn list<T: DeserializeOwned>(&self, opts: ListOptions) -> Result<Vec<(String, T)>> {
+        let iter = self
+            .0
+            .list(serde_wasm_bindgen::to_value(&opts)?.unchecked_into())
+            .map_err(WorkerError::from_js_err)?;
+
+        std::iter::from_fn(|| next_row(&iter)).collect()
+    }
+}
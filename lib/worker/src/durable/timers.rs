@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+
+use js_sys::Number;
+
+use super::{Namespace, ScheduledTime, Storage};
+use crate::result::Result;
+
+/// The prefix of the keys [`Timers::new`] stores deadlines under.
+const DEFAULT_PREFIX: &str = "__timers:";
+
+/// Named timers multiplexed on the single alarm of a Durable Object.
+///
+/// The deadline of every timer is kept in storage, and the alarm is always set
+/// to the earliest one. When the alarm fires, [`Timers::take_due`] returns the
+/// timers whose deadline has passed and sets the alarm to the next deadline.
+/// The timers own the alarm, so it should not also be set directly.
+///
+/// The deadline the alarm is set to is stored too, so that the timers are only
+/// listed when the earliest one is cancelled, moved later or becomes due.
+///
+/// ```ignore
+/// async fn fetch(&self, req: Request<Body>) -> Result<Response<Body>> {
+///     let mut timers = Timers::new(self.state.storage());
+///     timers.set("expire-session", Duration::from_secs(3600)).await?;
+///     timers.set("send-reminder", Duration::from_secs(60)).await?;
+///     // ...
+/// }
+///
+/// async fn alarm(&self, _info: AlarmInfo) -> Result<Response<Body>> {
+///     let mut timers = Timers::new(self.state.storage());
+///     for name in timers.take_due().await? {
+///         match name.as_str() {
+///             "expire-session" => self.expire_session().await?,
+///             "send-reminder" => self.send_reminder().await?,
+///             _ => {},
+///         }
+///     }
+///     Ok(Response::new(().into()))
+/// }
+/// ```
+pub struct Timers {
+    storage: Storage,
+    deadlines: Namespace<i64>,
+    /// The key of the deadline the alarm is set to.
+    armed_key: String,
+}
+
+impl Timers {
+    /// Creates timers stored under the keys starting with `__timers:`.
+    pub fn new(storage: Storage) -> Self {
+        Self::with_prefix(storage, DEFAULT_PREFIX)
+    }
+
+    /// Creates timers stored under the keys starting with `prefix`.
+    pub fn with_prefix(storage: Storage, prefix: impl Into<String>) -> Self {
+        let prefix = prefix.into();
+        Self {
+            deadlines: storage.namespace(format!("{prefix}deadline:")),
+            armed_key: format!("{prefix}armed"),
+            storage,
+        }
+    }
+
+    /// Sets the deadline of the timer `name`, replacing its previous deadline
+    /// if it was already set.
+    pub async fn set(&mut self, name: &str, at: impl Into<ScheduledTime>) -> Result<()> {
+        let deadline = at.into().schedule().get_time() as i64;
+        let previous = self.deadlines.get(name).await?;
+        self.deadlines.put(name, &deadline).await?;
+
+        match self.armed().await? {
+            Some(armed) if deadline >= armed && previous != Some(armed) => Ok(()),
+            // The timer is the earliest one now.
+            Some(armed) if deadline <= armed => self.arm_at(Some(deadline)).await,
+            // The earliest timer moved later, so another one may be earlier.
+            Some(_) => self.arm().await,
+            None => self.arm_at(Some(deadline)).await,
+        }
+    }
+
+    /// Cancels the timer `name`. Returns true if the timer was set or false if
+    /// it wasn't.
+    pub async fn cancel(&mut self, name: &str) -> Result<bool> {
+        let Some(deadline) = self.deadlines.get(name).await? else {
+            return Ok(false);
+        };
+        self.deadlines.delete(name).await?;
+        if self.armed().await? == Some(deadline) {
+            self.arm().await?;
+        }
+        Ok(true)
+    }
+
+    /// The deadline of the timer `name` as integer milliseconds since epoch, or
+    /// `None` if it isn't set.
+    pub async fn get(&self, name: &str) -> Result<Option<i64>> {
+        self.deadlines.get(name).await
+    }
+
+    /// The deadlines of all timers as integer milliseconds since epoch.
+    pub async fn list(&self) -> Result<BTreeMap<String, i64>> {
+        self.deadlines.list().await
+    }
+
+    /// The names of the timers whose deadline has passed, earliest first.
+    pub async fn due(&self) -> Result<Vec<String>> {
+        let now = crate::date::Date::now().as_millis() as i64;
+        let mut due = self
+            .deadlines
+            .list()
+            .await?
+            .into_iter()
+            .filter(|(_, deadline)| *deadline <= now)
+            .collect::<Vec<_>>();
+        due.sort_by_key(|(_, deadline)| *deadline);
+
+        Ok(due.into_iter().map(|(name, _)| name).collect())
+    }
+
+    /// Removes the timers whose deadline has passed and returns their names,
+    /// earliest first, then sets the alarm to the next deadline.
+    ///
+    /// The timers are removed before they are handled, so a timer whose
+    /// handling fails should be set again. Use [`Timers::due`] and
+    /// [`Timers::cancel`] to only remove timers once they have been handled.
+    pub async fn take_due(&mut self) -> Result<Vec<String>> {
+        let due = self.due().await?;
+        for name in &due {
+            self.deadlines.delete(name).await?;
+        }
+        self.arm().await?;
+
+        Ok(due)
+    }
+
+    /// The deadline the alarm was last set to by the timers.
+    async fn armed(&self) -> Result<Option<i64>> {
+        self.storage.get_opt(&self.armed_key).await
+    }
+
+    /// Sets the alarm to the earliest deadline, or deletes it if there is no
+    /// timer left.
+    async fn arm(&mut self) -> Result<()> {
+        let next = self.deadlines.list().await?.into_values().min();
+        self.arm_at(next).await
+    }
+
+    /// Sets the alarm to `next`, or deletes it if `None`, and stores it as the
+    /// armed deadline.
+    async fn arm_at(&mut self, next: Option<i64>) -> Result<()> {
+        match next {
+            Some(next) => {
+                let at = js_sys::Date::new(&Number::from(next as f64));
+                self.storage.set_alarm(ScheduledTime::new(at)).await?;
+                self.storage.put(&self.armed_key, next).await
+            },
+            None => {
+                self.storage.delete_alarm().await?;
+                self.storage.delete(&self.armed_key).await?;
+                Ok(())
+            },
+        }
+    }
+}
@@ -1,10 +1,15 @@
 use std::convert::TryFrom;
+use std::future::Future;
 
 use betterworker_sys::DynamicDispatcher as DynamicDispatcherSys;
-use js_sys::Object;
+use js_sys::{Object, Reflect};
 use send_wrapper::SendWrapper;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use wasm_bindgen::{JsCast, JsValue};
 
+use crate::body::Body;
+use crate::env::Env;
 use crate::error::WorkerError;
 use crate::fetcher::Fetcher;
 use crate::result::Result;
@@ -33,6 +38,28 @@ impl DynamicDispatcher {
             .map_err(WorkerError::from_js_err)?;
         Ok(fetcher_sys.into())
     }
+
+    /// Gets a [DispatchedWorker] for a Worker inside of the dispatch namespace,
+    /// applying the given limits and outbound parameters.
+    ///
+    /// ```ignore
+    /// let options = DispatchOptions::new()
+    ///     .cpu_ms(50)
+    ///     .sub_requests(10)
+    ///     .outbound(&Tenant { id: "customer-1" });
+    /// let worker = dispatcher.get_with_options("customer-worker", options)?;
+    /// let res = worker.fetch(req).await?;
+    /// ```
+    pub fn get_with_options(
+        &self, name: impl Into<String>, options: DispatchOptions,
+    ) -> Result<DispatchedWorker> {
+        let options = options.into_js()?;
+        let fetcher_sys = self
+            .0
+            .get(name.into(), options)
+            .map_err(classify_dispatch_err)?;
+        Ok(DispatchedWorker(fetcher_sys.into()))
+    }
 }
 
 impl AsRef<JsValue> for DynamicDispatcher {
@@ -61,3 +88,247 @@ impl From<DynamicDispatcher> for JsValue {
         JsValue::from(ns.0.take())
     }
 }
+
+/// The limits applied to a Worker dispatched with
+/// [DynamicDispatcher::get_with_options].
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DispatchLimits {
+    /// The maximum CPU time in milliseconds per request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_ms: Option<u32>,
+    /// The maximum number of subrequests per request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_requests: Option<u32>,
+}
+
+/// Options for [DynamicDispatcher::get_with_options].
+#[derive(Debug, Default)]
+pub struct DispatchOptions {
+    limits: DispatchLimits,
+    outbound: Option<serde_json::Result<serde_json::Value>>,
+}
+
+impl DispatchOptions {
+    /// Creates options without limits or outbound parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The maximum CPU time in milliseconds the Worker may use per request.
+    pub fn cpu_ms(mut self, cpu_ms: u32) -> Self {
+        self.limits.cpu_ms = Some(cpu_ms);
+        self
+    }
+
+    /// The maximum number of subrequests the Worker may make per request.
+    pub fn sub_requests(mut self, sub_requests: u32) -> Self {
+        self.limits.sub_requests = Some(sub_requests);
+        self
+    }
+
+    /// Sets all limits at once.
+    pub fn limits(mut self, limits: DispatchLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Parameters passed to the outbound Worker of the dispatch namespace,
+    /// which reads them with [outbound_params]. `params` must serialize to a
+    /// map. Serialization errors are returned by
+    /// [DynamicDispatcher::get_with_options].
+    pub fn outbound(mut self, params: impl Serialize) -> Self {
+        self.outbound = Some(serde_json::to_value(params));
+        self
+    }
+
+    fn into_js(self) -> Result<JsValue> {
+        #[derive(Serialize)]
+        struct Options {
+            limits: DispatchLimits,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            outbound: Option<serde_json::Value>,
+        }
+
+        let options = Options {
+            limits: self.limits,
+            outbound: self.outbound.transpose()?,
+        };
+        Ok(options.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+    }
+}
+
+/// A Worker inside of a dispatch namespace, returned by
+/// [DynamicDispatcher::get_with_options].
+///
+/// Unlike a plain [Fetcher], failed requests and RPC calls are reported as
+/// [WorkerError::DispatchWorkerNotFound] if the Worker does not exist and as
+/// [WorkerError::DispatchLimitExceeded] if it exceeded its limits.
+pub struct DispatchedWorker(pub(crate) Fetcher);
+
+impl DispatchedWorker {
+    /// Invoke a fetch event in the dispatched Worker.
+    pub async fn fetch(&self, req: http::Request<Body>) -> Result<http::Response<Body>> {
+        self.0.fetch_with_map_err(req, classify_dispatch_err).await
+    }
+
+    /// The Worker as a plain [Fetcher]. Errors of requests made through it
+    /// are not classified.
+    pub fn as_fetcher(&self) -> &Fetcher {
+        &self.0
+    }
+}
+
+impl From<DispatchedWorker> for Fetcher {
+    fn from(worker: DispatchedWorker) -> Self {
+        worker.0
+    }
+}
+
+/// The start of the message of the error the runtime throws when the
+/// dispatched Worker does not exist. The Workers for Platforms documentation
+/// on dynamic dispatch checks for the same prefix to answer `404`.
+const WORKER_NOT_FOUND_MESSAGE: &str = "Worker not found";
+
+/// The start of the messages of the errors the runtime throws when a
+/// dispatched Worker exceeds its limits. Unlike [WORKER_NOT_FOUND_MESSAGE],
+/// these messages are not documented, and were taken from the errors the
+/// runtime threw at the time of writing, so they may stop matching.
+const LIMIT_EXCEEDED_MESSAGES: &[&str] = &[
+    "Worker exceeded CPU time limit",
+    "Worker exceeded resource limits",
+    "Too many subrequests",
+];
+
+/// How a call to a dispatched Worker failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DispatchFailure {
+    WorkerNotFound,
+    LimitExceeded,
+    Other,
+}
+
+impl DispatchFailure {
+    /// Classifies the exception `err`.
+    fn of(err: &JsValue) -> Self {
+        let Some(error) = err.dyn_ref::<js_sys::Error>() else {
+            return Self::Other;
+        };
+        let remote = Reflect::get(err, &JsValue::from_str("remote"))
+            .ok()
+            .and_then(|remote| remote.as_bool())
+            .unwrap_or_default();
+        Self::classify(
+            &String::from(error.name()),
+            &String::from(error.message()),
+            remote,
+        )
+    }
+
+    /// Classifies an error by its `name`, `message` and `remote` flag.
+    ///
+    /// The runtime throws plain `Error`s without a code, so its errors are
+    /// told apart by the start of their message. Errors that came from the
+    /// code of the dispatched Worker, which have `remote` set, and errors of
+    /// any other type are never classified, and a change of wording makes an
+    /// error `Other` rather than misclassified.
+    fn classify(name: &str, message: &str, remote: bool) -> Self {
+        if remote || name != "Error" {
+            Self::Other
+        } else if message.starts_with(WORKER_NOT_FOUND_MESSAGE) {
+            Self::WorkerNotFound
+        } else if LIMIT_EXCEEDED_MESSAGES
+            .iter()
+            .any(|prefix| message.starts_with(prefix))
+        {
+            Self::LimitExceeded
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Maps the exceptions thrown by the runtime for dispatched Workers to typed
+/// errors.
+pub(crate) fn classify_dispatch_err(err: JsValue) -> WorkerError {
+    match DispatchFailure::of(&err) {
+        DispatchFailure::WorkerNotFound => {
+            WorkerError::DispatchWorkerNotFound(dispatch_err_message(&err))
+        },
+        DispatchFailure::LimitExceeded => {
+            WorkerError::DispatchLimitExceeded(dispatch_err_message(&err))
+        },
+        DispatchFailure::Other => WorkerError::from_promise_err(err),
+    }
+}
+
+fn dispatch_err_message(err: &JsValue) -> String {
+    err.unchecked_ref::<js_sys::Error>().message().into()
+}
+
+/// Reads the parameters passed to an outbound Worker with
+/// [DispatchOptions::outbound]. They are exposed as properties of the outbound
+/// Worker's `env`.
+pub fn outbound_params<T: DeserializeOwned>(env: &Env) -> Result<T> {
+    Ok(serde_wasm_bindgen::from_value(env.as_ref().clone())?)
+}
+
+/// Runs `handler` with the parameters passed through dispatch. Use it in the
+/// `#[event(fetch)]` handler of an outbound Worker.
+///
+/// ```ignore
+/// #[derive(Deserialize)]
+/// struct Tenant {
+///     id: String,
+/// }
+///
+/// #[event(fetch)]
+/// async fn main(req: Request<Body>, env: Env, _ctx: Context) -> Result<Response<Body>> {
+///     outbound(req, &env, |req, tenant: Tenant| async move {
+///         let mut req = req;
+///         req.headers_mut().insert("x-tenant", tenant.id.parse().unwrap());
+///         fetch(req).await
+///     })
+///     .await
+/// }
+/// ```
+pub async fn outbound<T, F, Fut>(
+    req: http::Request<Body>, env: &Env, handler: F,
+) -> Result<http::Response<Body>>
+where
+    T: DeserializeOwned,
+    F: FnOnce(http::Request<Body>, T) -> Fut,
+    Fut: Future<Output = Result<http::Response<Body>>>, {
+    let params = outbound_params(env)?;
+    handler(req, params).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_dispatch_errors() {
+        assert_eq!(
+            DispatchFailure::classify("Error", "Worker not found.", false),
+            DispatchFailure::WorkerNotFound
+        );
+        assert_eq!(
+            DispatchFailure::classify("Error", "Worker exceeded CPU time limit.", false),
+            DispatchFailure::LimitExceeded
+        );
+        assert_eq!(
+            DispatchFailure::classify("Error", "boom", false),
+            DispatchFailure::Other
+        );
+        // Errors thrown by the dispatched Worker itself are passed through.
+        assert_eq!(
+            DispatchFailure::classify("Error", "Worker not found.", true),
+            DispatchFailure::Other
+        );
+        assert_eq!(
+            DispatchFailure::classify("TypeError", "Worker not found.", false),
+            DispatchFailure::Other
+        );
+    }
+}
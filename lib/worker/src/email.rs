@@ -0,0 +1,215 @@
+//! [Email Workers](https://developers.cloudflare.com/email-routing/email-workers/)
+//! receive incoming emails with the `#[event(email)]` handler and send emails
+//! with a [`SendEmail`] binding.
+//!
+//! ```ignore
+//! #[event(email)]
+//! pub async fn email(message: ForwardableEmailMessage, _env: Env, _ctx: Context) -> Result<()> {
+//!     if message.from() == "spam@example.com" {
+//!         message.set_reject("Address is blocked");
+//!         return Ok(());
+//!     }
+//!
+//!     message.forward("inbox@example.com", None).await
+//! }
+//! ```
+
+pub mod mime;
+
+use std::convert::TryFrom;
+
+use betterworker_sys::ext::HeadersExt;
+use betterworker_sys::{
+    EmailMessage as EmailMessageSys, ForwardableEmailMessage as ForwardableEmailMessageSys,
+    SendEmail as SendEmailSys,
+};
+use js_sys::Object;
+use send_wrapper::SendWrapper;
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::body::Body;
+use crate::error::WorkerError;
+use crate::futures::future_from_promise;
+use crate::result::Result;
+
+/// An incoming email, passed to the `#[event(email)]` handler.
+#[derive(Debug)]
+pub struct ForwardableEmailMessage(SendWrapper<ForwardableEmailMessageSys>);
+
+impl ForwardableEmailMessage {
+    /// Constructs a message from the underlying JavaScript object.
+    pub fn new(inner: ForwardableEmailMessageSys) -> Self {
+        Self(SendWrapper::new(inner))
+    }
+
+    /// The `MAIL FROM` address of the envelope.
+    pub fn from(&self) -> String {
+        self.0.from()
+    }
+
+    /// The `RCPT TO` address of the envelope.
+    pub fn to(&self) -> String {
+        self.0.to()
+    }
+
+    /// The headers of the message. Fails if a header is not a valid HTTP
+    /// header.
+    pub fn headers(&self) -> Result<http::HeaderMap> {
+        let mut headers = http::HeaderMap::new();
+        for header in self.0.headers().entries() {
+            let header = header
+                .map_err(WorkerError::from_js_err)?
+                .unchecked_into::<js_sys::Array>();
+            let (Some(name), Some(value)) = (header.get(0).as_string(), header.get(1).as_string())
+            else {
+                continue;
+            };
+            let name = http::HeaderName::try_from(&name)
+                .map_err(|err| WorkerError::MimeError(format!("header {name:?}: {err}")))?;
+            let value = http::HeaderValue::try_from(value)
+                .map_err(|err| WorkerError::MimeError(format!("header {name}: {err}")))?;
+            headers.append(name, value);
+        }
+        Ok(headers)
+    }
+
+    /// A stream of the raw RFC 5322 message. Use [`mime::Message::parse`] to
+    /// inspect it.
+    pub fn raw(&self) -> Body {
+        Body::from(self.0.raw())
+    }
+
+    /// The size of the raw message in bytes.
+    pub fn raw_size(&self) -> u64 {
+        self.0.raw_size() as u64
+    }
+
+    /// Rejects the message with a permanent SMTP error carrying `reason`.
+    pub fn set_reject(&self, reason: &str) {
+        self.0.set_reject(reason)
+    }
+
+    /// Forwards the message to a verified destination address, optionally
+    /// adding `headers`. Only `X-*` headers may be added.
+    pub async fn forward(&self, rcpt_to: &str, headers: Option<http::HeaderMap>) -> Result<()> {
+        let fut = {
+            let headers = headers
+                .map(|headers| {
+                    let js_headers = web_sys::Headers::new().map_err(WorkerError::from_js_err)?;
+                    for (name, value) in headers.iter() {
+                        if let Ok(value) = value.to_str() {
+                            js_headers
+                                .append(name.as_str(), value)
+                                .map_err(WorkerError::from_js_err)?;
+                        }
+                    }
+                    Ok::<_, WorkerError>(js_headers)
+                })
+                .transpose()?;
+            let promise = self
+                .0
+                .forward(rcpt_to, headers)
+                .map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(())
+    }
+
+    /// Replies to the sender of this message. The reply must reference the
+    /// original message with an `In-Reply-To` header.
+    pub async fn reply(&self, message: EmailMessage) -> Result<()> {
+        let fut = {
+            let promise = self.0.reply(&message.0).map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(())
+    }
+}
+
+impl AsRef<JsValue> for ForwardableEmailMessage {
+    fn as_ref(&self) -> &JsValue {
+        &self.0
+    }
+}
+
+/// An outgoing email, sent with [`SendEmail::send`] or
+/// [`ForwardableEmailMessage::reply`].
+#[derive(Debug)]
+pub struct EmailMessage(SendWrapper<EmailMessageSys>);
+
+impl EmailMessage {
+    /// Creates a message from the envelope addresses and the raw RFC 5322
+    /// message.
+    pub fn new(from: &str, to: &str, raw: impl Into<Body>) -> Result<Self> {
+        let raw = raw
+            .into()
+            .into_stream()
+            .map(JsValue::from)
+            .unwrap_or_else(|| JsValue::from_str(""));
+        let inner = EmailMessageSys::new(from, to, &raw).map_err(WorkerError::from_js_err)?;
+        Ok(Self(SendWrapper::new(inner)))
+    }
+
+    /// Creates a message from the envelope addresses and a message built with
+    /// [`mime::MessageBuilder`].
+    pub fn from_mime(from: &str, to: &str, message: &mime::MessageBuilder) -> Result<Self> {
+        Self::new(from, to, message.build()?)
+    }
+
+    /// The `MAIL FROM` address of the envelope.
+    pub fn from(&self) -> String {
+        self.0.from()
+    }
+
+    /// The `RCPT TO` address of the envelope.
+    pub fn to(&self) -> String {
+        self.0.to()
+    }
+}
+
+/// A binding for sending emails to verified addresses.
+pub struct SendEmail(SendWrapper<SendEmailSys>);
+
+impl SendEmail {
+    /// Sends the message.
+    pub async fn send(&self, message: EmailMessage) -> Result<()> {
+        let fut = {
+            let promise = self.0.send(&message.0).map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(())
+    }
+}
+
+impl AsRef<JsValue> for SendEmail {
+    fn as_ref(&self) -> &JsValue {
+        &self.0
+    }
+}
+
+impl TryFrom<Object> for SendEmail {
+    type Error = WorkerError;
+
+    fn try_from(obj: Object) -> Result<Self> {
+        const TYPE_NAME: &str = "SendEmail";
+
+        let data = if obj.constructor().name() == TYPE_NAME {
+            obj.unchecked_into()
+        } else {
+            return Err(WorkerError::InvalidBinding);
+        };
+        Ok(Self(SendWrapper::new(data)))
+    }
+}
+
+impl From<SendEmail> for JsValue {
+    fn from(binding: SendEmail) -> Self {
+        JsValue::from(binding.0.take())
+    }
+}
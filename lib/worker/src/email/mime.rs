@@ -0,0 +1,832 @@
+//! Building and parsing of [RFC 5322](https://datatracker.ietf.org/doc/html/rfc5322)
+//! messages with [MIME](https://datatracker.ietf.org/doc/html/rfc2045) bodies.
+//!
+//! # Example
+//!
+//! ```
+//! use betterworker::email::mime::{Message, MessageBuilder};
+//!
+//! let raw = MessageBuilder::new()
+//!     .from("Sender <sender@example.com>")
+//!     .to("recipient@example.com")
+//!     .subject("Hello")
+//!     .message_id("<hello@example.com>")
+//!     .text("Hello, world!")
+//!     .html("<p>Hello, world!</p>")
+//!     .build()
+//!     .unwrap();
+//!
+//! let message = Message::parse(&raw).unwrap();
+//! assert_eq!(message.subject(), Some("Hello"));
+//! assert_eq!(message.text().as_deref(), Some("Hello, world!"));
+//! ```
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::error::WorkerError;
+use crate::result::Result;
+
+/// Maximum line length of encoded bodies, excluding the line break.
+const LINE_LENGTH: usize = 76;
+
+/// A single header field of a [`Message`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// The field name, as it appears in the message.
+    pub name: String,
+    /// The unfolded field value with any encoded words decoded.
+    pub value: String,
+}
+
+/// A parsed message, or a single part of a multipart message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    headers: Vec<Header>,
+    body: Vec<u8>,
+    parts: Vec<Message>,
+}
+
+impl Message {
+    /// Parses a raw message.
+    pub fn parse(raw: impl AsRef<[u8]>) -> Result<Self> {
+        let raw = raw.as_ref();
+        let (head, body) = split_head(raw);
+        let headers = parse_headers(head)?;
+
+        let mut message = Self {
+            headers,
+            body: Vec::new(),
+            parts: Vec::new(),
+        };
+
+        if message.is_multipart() {
+            let boundary = message.content_type_param("boundary").ok_or_else(|| {
+                WorkerError::MimeError("multipart message has no boundary".into())
+            })?;
+            message.parts = split_multipart(body, &boundary)
+                .into_iter()
+                .map(Message::parse)
+                .collect::<Result<_>>()?;
+        } else {
+            let encoding = message
+                .header("Content-Transfer-Encoding")
+                .map(|encoding| encoding.trim().to_ascii_lowercase());
+            message.body = match encoding.as_deref() {
+                Some("base64") => STANDARD
+                    .decode(
+                        body.iter()
+                            .copied()
+                            .filter(|b| !b.is_ascii_whitespace())
+                            .collect::<Vec<_>>(),
+                    )
+                    .map_err(|e| WorkerError::MimeError(e.to_string()))?,
+                Some("quoted-printable") => decode_quoted_printable(body),
+                _ => body.to_vec(),
+            };
+        }
+
+        Ok(message)
+    }
+
+    /// All header fields in the order they appear.
+    pub fn headers(&self) -> &[Header] {
+        &self.headers
+    }
+
+    /// The value of the first header field with the given name, compared
+    /// case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case(name))
+            .map(|header| header.value.as_str())
+    }
+
+    /// The values of all header fields with the given name, compared
+    /// case-insensitively.
+    pub fn header_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.headers
+            .iter()
+            .filter(move |header| header.name.eq_ignore_ascii_case(name))
+            .map(|header| header.value.as_str())
+    }
+
+    /// The `Subject` header.
+    pub fn subject(&self) -> Option<&str> {
+        self.header("Subject")
+    }
+
+    /// The `From` header.
+    pub fn from(&self) -> Option<&str> {
+        self.header("From")
+    }
+
+    /// The `To` header.
+    pub fn to(&self) -> Option<&str> {
+        self.header("To")
+    }
+
+    /// The `Message-ID` header.
+    pub fn message_id(&self) -> Option<&str> {
+        self.header("Message-ID")
+    }
+
+    /// The lowercased media type from the `Content-Type` header, defaulting to
+    /// `text/plain`.
+    pub fn content_type(&self) -> String {
+        self.header("Content-Type")
+            .and_then(|value| value.split(';').next())
+            .map(|ty| ty.trim().to_ascii_lowercase())
+            .filter(|ty| !ty.is_empty())
+            .unwrap_or_else(|| "text/plain".to_string())
+    }
+
+    /// A parameter of the `Content-Type` header, such as `charset` or
+    /// `boundary`.
+    pub fn content_type_param(&self, name: &str) -> Option<String> {
+        self.header("Content-Type")
+            .and_then(|value| header_param(value, name))
+    }
+
+    /// The file name of an attachment, from the `Content-Disposition` or
+    /// `Content-Type` header.
+    pub fn filename(&self) -> Option<String> {
+        self.header("Content-Disposition")
+            .and_then(|value| header_param(value, "filename"))
+            .or_else(|| self.content_type_param("name"))
+    }
+
+    /// Whether this message has a `multipart/*` content type.
+    pub fn is_multipart(&self) -> bool {
+        self.content_type().starts_with("multipart/")
+    }
+
+    /// Whether this part is marked as an attachment.
+    pub fn is_attachment(&self) -> bool {
+        self.header("Content-Disposition")
+            .map(|value| {
+                value
+                    .trim_start()
+                    .to_ascii_lowercase()
+                    .starts_with("attachment")
+            })
+            .unwrap_or(false)
+    }
+
+    /// The parts of a multipart message. Empty for other messages.
+    pub fn parts(&self) -> &[Message] {
+        &self.parts
+    }
+
+    /// The decoded body of a non-multipart message.
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// The decoded body as text, converted from the part's `charset`.
+    pub fn body_text(&self) -> String {
+        let charset = self
+            .content_type_param("charset")
+            .map(|charset| charset.to_ascii_lowercase());
+        decode_charset(charset.as_deref().unwrap_or("us-ascii"), &self.body)
+    }
+
+    /// The first `text/plain` part that is not an attachment.
+    pub fn text(&self) -> Option<String> {
+        self.find_text("text/plain")
+    }
+
+    /// The first `text/html` part that is not an attachment.
+    pub fn html(&self) -> Option<String> {
+        self.find_text("text/html")
+    }
+
+    /// All parts marked as attachments, in depth-first order.
+    pub fn attachments(&self) -> Vec<&Message> {
+        let mut attachments = Vec::new();
+        self.collect_attachments(&mut attachments);
+        attachments
+    }
+
+    fn find_text(&self, content_type: &str) -> Option<String> {
+        if self.is_multipart() {
+            self.parts
+                .iter()
+                .find_map(|part| part.find_text(content_type))
+        } else if !self.is_attachment() && self.content_type() == content_type {
+            Some(self.body_text())
+        } else {
+            None
+        }
+    }
+
+    fn collect_attachments<'a>(&'a self, attachments: &mut Vec<&'a Message>) {
+        if self.is_attachment() {
+            attachments.push(self);
+        }
+        for part in &self.parts {
+            part.collect_attachments(attachments);
+        }
+    }
+}
+
+/// A file attached to a message built with [`MessageBuilder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    /// The file name presented to the recipient.
+    pub filename: String,
+    /// The media type of the file, e.g. `application/pdf`.
+    pub content_type: String,
+    /// The contents of the file.
+    pub content: Vec<u8>,
+}
+
+/// Builds a raw RFC 5322 message.
+///
+/// Plain text and HTML bodies are sent as `multipart/alternative` when both are
+/// set, and attachments wrap the body in `multipart/mixed`. Address headers
+/// are written as given, so display names must already be encoded if they
+/// contain non-ASCII characters.
+///
+/// Cloudflare requires a `Message-ID` header on every sent message.
+#[derive(Debug, Clone, Default)]
+pub struct MessageBuilder {
+    headers: Vec<(String, String)>,
+    text: Option<String>,
+    html: Option<String>,
+    attachments: Vec<Attachment>,
+}
+
+impl MessageBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `From` header.
+    pub fn from(self, address: impl Into<String>) -> Self {
+        self.set_header("From", address)
+    }
+
+    /// Adds a recipient to the `To` header.
+    pub fn to(self, address: impl Into<String>) -> Self {
+        self.add_address("To", address)
+    }
+
+    /// Adds a recipient to the `Cc` header.
+    pub fn cc(self, address: impl Into<String>) -> Self {
+        self.add_address("Cc", address)
+    }
+
+    /// Sets the `Reply-To` header.
+    pub fn reply_to(self, address: impl Into<String>) -> Self {
+        self.set_header("Reply-To", address)
+    }
+
+    /// Sets the `Subject` header.
+    pub fn subject(self, subject: impl Into<String>) -> Self {
+        self.set_header("Subject", subject)
+    }
+
+    /// Sets the `Message-ID` header, e.g. `<unique-id@example.com>`.
+    pub fn message_id(self, id: impl Into<String>) -> Self {
+        self.set_header("Message-ID", id)
+    }
+
+    /// Sets the `In-Reply-To` header to the `Message-ID` of the message this
+    /// is a reply to.
+    pub fn in_reply_to(self, id: impl Into<String>) -> Self {
+        self.set_header("In-Reply-To", id)
+    }
+
+    /// Sets the `References` header.
+    pub fn references(self, ids: impl Into<String>) -> Self {
+        self.set_header("References", ids)
+    }
+
+    /// Sets the `Date` header, e.g. `Thu, 02 Sep 2021 19:38:31 +0000`.
+    pub fn date(self, date: impl Into<String>) -> Self {
+        self.set_header("Date", date)
+    }
+
+    /// Adds an arbitrary header. Values containing non-ASCII characters are
+    /// written as RFC 2047 encoded words.
+    ///
+    /// [`build`](Self::build) fails if the name is not a valid RFC 5322 field
+    /// name, or if the value contains a line break.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the plain text body.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// Sets the HTML body.
+    pub fn html(mut self, html: impl Into<String>) -> Self {
+        self.html = Some(html.into());
+        self
+    }
+
+    /// Adds an attachment.
+    pub fn attachment(mut self, attachment: Attachment) -> Self {
+        self.attachments.push(attachment);
+        self
+    }
+
+    /// Writes the raw message. Fails if `From` or `To` is missing, or if a
+    /// header or attachment could be used to inject headers.
+    pub fn build(&self) -> Result<String> {
+        for required in ["From", "To"] {
+            if !self
+                .headers
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case(required))
+            {
+                return Err(WorkerError::MimeError(format!(
+                    "message has no {required} header"
+                )));
+            }
+        }
+        for (name, value) in &self.headers {
+            validate_header(name, value)?;
+        }
+        for attachment in &self.attachments {
+            validate_header("Content-Type", &attachment.content_type)?;
+            validate_header("Content-Disposition", &attachment.filename)?;
+        }
+
+        let mut out = String::new();
+        for (name, value) in &self.headers {
+            let value = if is_address_header(name) {
+                value.clone()
+            } else {
+                encode_header_value(value)
+            };
+            out.push_str(&format!("{name}: {value}\r\n"));
+        }
+        out.push_str("MIME-Version: 1.0\r\n");
+        out.push_str(&self.body_part());
+        Ok(out)
+    }
+
+    fn set_header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers
+            .retain(|(existing, _)| !existing.eq_ignore_ascii_case(name));
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+
+    fn add_address(mut self, name: &str, address: impl Into<String>) -> Self {
+        let address = address.into();
+        match self
+            .headers
+            .iter_mut()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+        {
+            Some((_, value)) => {
+                value.push_str(", ");
+                value.push_str(&address);
+            },
+            None => self.headers.push((name.to_string(), address)),
+        }
+        self
+    }
+
+    /// Writes the `Content-*` headers and the body of the message.
+    fn body_part(&self) -> String {
+        let text = self.text.as_ref().map(|text| text_part("text/plain", text));
+        let html = self.html.as_ref().map(|html| text_part("text/html", html));
+
+        let body = match (text, html) {
+            (Some(text), Some(html)) => multipart("alternative", vec![text, html]),
+            (Some(part), None) | (None, Some(part)) => part,
+            (None, None) => text_part("text/plain", ""),
+        };
+
+        if self.attachments.is_empty() {
+            return body;
+        }
+
+        let mut parts = vec![body];
+        parts.extend(self.attachments.iter().map(attachment_part));
+        multipart("mixed", parts)
+    }
+}
+
+/// Fails if `name` is not an RFC 5322 field name, or if `value` contains a
+/// line break, either of which would let the header end early and start
+/// another one.
+fn validate_header(name: &str, value: &str) -> Result<()> {
+    let valid_name = !name.is_empty() && name.bytes().all(|b| (33..=126).contains(&b) && b != b':');
+    if !valid_name {
+        return Err(WorkerError::MimeError(format!(
+            "invalid header name {name:?}"
+        )));
+    }
+    if value.contains(['\r', '\n']) {
+        return Err(WorkerError::MimeError(format!(
+            "the value of the {name} header contains a line break"
+        )));
+    }
+    Ok(())
+}
+
+fn is_address_header(name: &str) -> bool {
+    ["From", "To", "Cc", "Bcc", "Reply-To", "Sender"]
+        .iter()
+        .any(|header| header.eq_ignore_ascii_case(name))
+}
+
+fn text_part(content_type: &str, text: &str) -> String {
+    let needs_encoding = !text.is_ascii() || text.lines().any(|line| line.len() > 998);
+    if needs_encoding {
+        format!(
+            "Content-Type: {content_type}; charset=utf-8\r\nContent-Transfer-Encoding: \
+             base64\r\n\r\n{}",
+            encode_base64_lines(text.as_bytes())
+        )
+    } else {
+        format!(
+            "Content-Type: {content_type}; charset=utf-8\r\nContent-Transfer-Encoding: \
+             7bit\r\n\r\n{}\r\n",
+            text.replace("\r\n", "\n").replace('\n', "\r\n")
+        )
+    }
+}
+
+fn attachment_part(attachment: &Attachment) -> String {
+    let filename = encode_header_value(&attachment.filename).replace('"', "\\\"");
+    format!(
+        "Content-Type: {}; name=\"{filename}\"\r\nContent-Disposition: attachment; \
+         filename=\"{filename}\"\r\nContent-Transfer-Encoding: base64\r\n\r\n{}",
+        attachment.content_type,
+        encode_base64_lines(&attachment.content)
+    )
+}
+
+fn multipart(subtype: &str, parts: Vec<String>) -> String {
+    let boundary = boundary_for(&parts);
+    let mut out = format!(
+        "Content-Type: multipart/{subtype}; boundary=\"{boundary}\"\r\n\r\nThis is a multi-part \
+         message in MIME format.\r\n"
+    );
+    for part in parts {
+        out.push_str(&format!("--{boundary}\r\n{part}"));
+        if !part.ends_with("\r\n") {
+            out.push_str("\r\n");
+        }
+    }
+    out.push_str(&format!("--{boundary}--\r\n"));
+    out
+}
+
+/// Derives a boundary from the contents of the parts, so builds are
+/// deterministic, and makes sure it does not occur in any of them.
+fn boundary_for(parts: &[String]) -> String {
+    // FNV-1a
+    let mut hash: u64 = 0xCBF29CE484222325;
+    for byte in parts.iter().flat_map(|part| part.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001B3);
+    }
+
+    loop {
+        let boundary = format!("=_betterworker_{hash:016x}");
+        if !parts.iter().any(|part| part.contains(&boundary)) {
+            return boundary;
+        }
+        hash = hash.wrapping_add(1);
+    }
+}
+
+fn encode_base64_lines(bytes: &[u8]) -> String {
+    let encoded = STANDARD.encode(bytes);
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / LINE_LENGTH * 2 + 2);
+    for line in encoded.as_bytes().chunks(LINE_LENGTH) {
+        // base64 output is always ASCII
+        out.push_str(std::str::from_utf8(line).unwrap());
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Encodes a header value as RFC 2047 encoded words if it is not plain ASCII.
+fn encode_header_value(value: &str) -> String {
+    if value.is_ascii() && !value.contains("=?") {
+        return value.to_string();
+    }
+
+    // Each encoded word may be at most 75 characters long, which leaves room for
+    // 45 bytes of input.
+    let mut words = Vec::new();
+    let mut chunk = String::new();
+    for c in value.chars() {
+        if chunk.len() + c.len_utf8() > 45 {
+            words.push(format!("=?utf-8?B?{}?=", STANDARD.encode(&chunk)));
+            chunk.clear();
+        }
+        chunk.push(c);
+    }
+    if !chunk.is_empty() {
+        words.push(format!("=?utf-8?B?{}?=", STANDARD.encode(&chunk)));
+    }
+    words.join("\r\n ")
+}
+
+/// Splits a message into its header section and body.
+fn split_head(raw: &[u8]) -> (&[u8], &[u8]) {
+    let mut start = 0;
+    while start < raw.len() {
+        let end = raw[start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map(|i| start + i + 1)
+            .unwrap_or(raw.len());
+        let line = &raw[start..end];
+        if line == b"\r\n" || line == b"\n" {
+            return (&raw[..start], &raw[end..]);
+        }
+        start = end;
+    }
+    (raw, &[])
+}
+
+fn parse_headers(head: &[u8]) -> Result<Vec<Header>> {
+    let head = String::from_utf8_lossy(head);
+    let mut fields: Vec<String> = Vec::new();
+    for line in head.lines() {
+        if line.starts_with([' ', '\t']) {
+            match fields.last_mut() {
+                Some(field) => field.push_str(line),
+                None => {
+                    return Err(WorkerError::MimeError(
+                        "message starts with a folded header line".into(),
+                    ))
+                },
+            }
+        } else if !line.is_empty() {
+            fields.push(line.to_string());
+        }
+    }
+
+    fields
+        .into_iter()
+        .map(|field| {
+            let (name, value) = field
+                .split_once(':')
+                .ok_or_else(|| WorkerError::MimeError(format!("invalid header line: {field}")))?;
+            Ok(Header {
+                name: name.trim().to_string(),
+                value: decode_encoded_words(value.trim()),
+            })
+        })
+        .collect()
+}
+
+/// Reads a `key=value` parameter from a structured header value.
+fn header_param(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        if !key.trim().eq_ignore_ascii_case(name) {
+            return None;
+        }
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .map(|value| value.replace("\\\"", "\""))
+            .unwrap_or_else(|| value.to_string());
+        Some(value)
+    })
+}
+
+/// Splits the body of a multipart message into the raw parts between the
+/// boundary delimiters.
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = Vec::new();
+    let mut part_start = None;
+    let mut start = 0;
+
+    while start < body.len() {
+        let end = body[start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map(|i| start + i + 1)
+            .unwrap_or(body.len());
+        let line = trim_line_end(&body[start..end]);
+
+        if let Some(rest) = line.strip_prefix(delimiter.as_bytes()) {
+            if let Some(part_start) = part_start {
+                parts.push(trim_line_end(&body[part_start..start]));
+            }
+            if rest.starts_with(b"--") {
+                return parts;
+            }
+            part_start = Some(end);
+        }
+        start = end;
+    }
+
+    // Tolerate a missing closing delimiter.
+    if let Some(part_start) = part_start {
+        parts.push(&body[part_start..]);
+    }
+    parts
+}
+
+fn trim_line_end(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+fn decode_quoted_printable(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        match input[i] {
+            b'=' if input[i + 1..].starts_with(b"\r\n") => i += 3,
+            b'=' if input[i + 1..].starts_with(b"\n") => i += 2,
+            b'=' => match input.get(i + 1..i + 3).and_then(decode_hex_pair) {
+                Some(byte) => {
+                    out.push(byte);
+                    i += 3;
+                },
+                None => {
+                    out.push(b'=');
+                    i += 1;
+                },
+            },
+            byte => {
+                out.push(byte);
+                i += 1;
+            },
+        }
+    }
+    out
+}
+
+fn decode_hex_pair(pair: &[u8]) -> Option<u8> {
+    let pair = std::str::from_utf8(pair).ok()?;
+    u8::from_str_radix(pair, 16).ok()
+}
+
+fn decode_charset(charset: &str, bytes: &[u8]) -> String {
+    match charset {
+        "iso-8859-1" | "latin1" => bytes.iter().map(|&b| b as char).collect(),
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// Decodes RFC 2047 encoded words, e.g. `=?utf-8?B?SGVsbG8=?=`. Whitespace
+/// between adjacent encoded words is dropped.
+fn decode_encoded_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut pending_whitespace = String::new();
+    let mut last_was_word = false;
+
+    while !rest.is_empty() {
+        if let Some((decoded, len)) = decode_encoded_word(rest) {
+            if !last_was_word {
+                out.push_str(&pending_whitespace);
+            }
+            pending_whitespace.clear();
+            out.push_str(&decoded);
+            rest = &rest[len..];
+            last_was_word = true;
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap();
+        if c.is_whitespace() {
+            pending_whitespace.push(c);
+        } else {
+            out.push_str(&pending_whitespace);
+            pending_whitespace.clear();
+            out.push(c);
+            last_was_word = false;
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+
+    out.push_str(&pending_whitespace);
+    out
+}
+
+/// Decodes a single encoded word at the start of `input`, returning the
+/// decoded text and the length of the encoded word.
+fn decode_encoded_word(input: &str) -> Option<(String, usize)> {
+    let inner = input.strip_prefix("=?")?;
+    let (charset, inner) = inner.split_once('?')?;
+    let (encoding, inner) = inner.split_once('?')?;
+    let end = inner.find("?=")?;
+    let text = &inner[..end];
+    if text.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let bytes = match encoding {
+        "B" | "b" => STANDARD.decode(text).ok()?,
+        "Q" | "q" => decode_quoted_printable(text.replace('_', " ").as_bytes()),
+        _ => return None,
+    };
+    // RFC 2231 allows a language suffix, e.g. `utf-8*en`.
+    let charset = charset.split('*').next()?.to_ascii_lowercase();
+    let len = input.len() - inner.len() + end + 2;
+    Some((decode_charset(&charset, &bytes), len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_multipart_message() {
+        let raw = MessageBuilder::new()
+            .from("Sender <sender@example.com>")
+            .to("one@example.com")
+            .to("two@example.com")
+            .subject("Grüße")
+            .message_id("<id@example.com>")
+            .text("Hello,\nworld!")
+            .html("<p>Hällo</p>")
+            .attachment(Attachment {
+                filename: "data.bin".into(),
+                content_type: "application/octet-stream".into(),
+                content: vec![0, 1, 2, 255],
+            })
+            .build()
+            .unwrap();
+
+        let message = Message::parse(&raw).unwrap();
+        assert_eq!(message.subject(), Some("Grüße"));
+        assert_eq!(message.to(), Some("one@example.com, two@example.com"));
+        assert_eq!(message.content_type(), "multipart/mixed");
+        assert_eq!(message.text().as_deref(), Some("Hello,\r\nworld!"));
+        assert_eq!(message.html().as_deref(), Some("<p>Hällo</p>"));
+
+        let attachments = message.attachments();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].filename().as_deref(), Some("data.bin"));
+        assert_eq!(attachments[0].body(), &[0, 1, 2, 255]);
+    }
+
+    #[test]
+    fn requires_from_and_to() {
+        assert!(MessageBuilder::new().to("a@example.com").build().is_err());
+        assert!(MessageBuilder::new().from("a@example.com").build().is_err());
+    }
+
+    #[test]
+    fn matches_required_headers_case_insensitively() {
+        let raw = MessageBuilder::new()
+            .header("from", "a@example.com")
+            .header("to", "b@example.com")
+            .build()
+            .unwrap();
+        assert!(raw.starts_with("from: a@example.com\r\nto: b@example.com\r\n"));
+    }
+
+    #[test]
+    fn rejects_header_injection() {
+        let builder = MessageBuilder::new()
+            .from("a@example.com")
+            .to("b@example.com");
+        let injected = [
+            builder.clone().subject("Hi\r\nBcc: c@example.com"),
+            builder.clone().subject("Grüße\nBcc: c@example.com"),
+            builder.clone().to("c@example.com\r\nBcc: d@example.com"),
+            builder.clone().header("X-Tag\r\nBcc", "c@example.com"),
+            builder.clone().header("X Tag", "value"),
+            builder.clone().header("X-Tag:", "value"),
+            builder.clone().header("", "value"),
+            builder.clone().attachment(Attachment {
+                filename: "a.txt\r\nBcc: c@example.com".into(),
+                content_type: "text/plain".into(),
+                content: vec![],
+            }),
+        ];
+        for builder in injected {
+            assert!(matches!(builder.build(), Err(WorkerError::MimeError(_))));
+        }
+    }
+
+    #[test]
+    fn parses_folded_and_encoded_headers() {
+        let raw = "Subject: =?UTF-8?Q?caf=C3=A9?=\r\n =?utf-8?B?IGF1IGxhaXQ=?=\r\nX-Long: \
+                   one\r\n\ttwo\r\nContent-Type: text/plain; \
+                   charset=iso-8859-1\r\nContent-Transfer-Encoding: \
+                   quoted-printable\r\n\r\ncaf=E9 =\r\nnoir";
+
+        let message = Message::parse(raw).unwrap();
+        assert_eq!(message.subject(), Some("café au lait"));
+        assert_eq!(message.header("x-long"), Some("one\ttwo"));
+        assert_eq!(message.text().as_deref(), Some("café noir"));
+    }
+}
@@ -6,22 +6,32 @@ use send_wrapper::SendWrapper;
 use wasm_bindgen::{JsCast, JsValue};
 use worker_kv::KvStore;
 
+use crate::assets::Assets;
 #[cfg(feature = "d1")]
 use crate::d1::Database;
 use crate::durable::ObjectNamespace;
 use crate::dynamic_dispatch::DynamicDispatcher;
+use crate::email::SendEmail;
 use crate::error::WorkerError;
 use crate::fetcher::Fetcher;
 use crate::prelude::Bucket;
 #[cfg(feature = "queue")]
 use crate::queue::Queue;
+use crate::rate_limit::RateLimiter;
 use crate::result::Result;
+use crate::workflow::Workflow;
 
 /// Env contains any bindings you have associated with the Worker when you
 /// uploaded it.
 #[derive(Clone)]
 pub struct Env(SendWrapper<EnvSys>);
 
+impl AsRef<JsValue> for Env {
+    fn as_ref(&self) -> &JsValue {
+        &self.0
+    }
+}
+
 impl From<EnvSys> for Env {
     fn from(env: EnvSys) -> Self {
         Self(SendWrapper::new(env))
@@ -59,6 +69,12 @@ impl Env {
         KvStore::from_this(self.0.as_ref(), binding).map_err(From::from)
     }
 
+    /// Access a static assets binding by the binding name configured in your
+    /// wrangler.toml file.
+    pub fn assets(&self, binding: &str) -> Result<Assets> {
+        self.get_binding(binding)
+    }
+
     /// Access a Durable Object namespace by the binding name configured in your
     /// wrangler.toml file.
     pub fn durable_object(&self, binding: &str) -> Result<ObjectNamespace> {
@@ -76,6 +92,12 @@ impl Env {
         self.get_binding(binding)
     }
 
+    /// Access a [Send Email](https://developers.cloudflare.com/email-routing/email-workers/send-email-workers/)
+    /// binding by the binding name configured in your wrangler.toml file.
+    pub fn send_email(&self, binding: &str) -> Result<SendEmail> {
+        self.get_binding(binding)
+    }
+
     #[cfg(feature = "queue")]
     /// Access a Queue by the binding name configured in your wrangler.toml
     /// file.
@@ -89,6 +111,12 @@ impl Env {
         self.get_binding(binding)
     }
 
+    /// Access a [Rate Limiter](https://developers.cloudflare.com/workers/runtime-apis/bindings/rate-limit/)
+    /// by the binding name configured in your wrangler.toml file.
+    pub fn rate_limiter(&self, binding: &str) -> Result<RateLimiter> {
+        self.get_binding(binding)
+    }
+
     /// Access a D1 Database by the binding name configured in your
     /// wrangler.toml file.
     #[cfg(feature = "d1")]
@@ -96,6 +124,12 @@ impl Env {
         self.get_binding(binding).map_err(WorkerError::from)
     }
 
+    /// Access a Workflow by the binding name configured in your wrangler.toml
+    /// file.
+    pub fn workflow(&self, binding: &str) -> Result<Workflow> {
+        self.get_binding(binding)
+    }
+
     #[doc(hidden)]
     pub fn _inner(self) -> EnvSys {
         self.0.take()
@@ -40,6 +40,9 @@ pub enum WorkerError {
     #[error(transparent)]
     Utf8Error(#[from] std::string::FromUtf8Error),
 
+    #[error(transparent)]
+    InvalidUriParts(#[from] http::uri::InvalidUriParts),
+
     #[error(transparent)]
     SerdeJsonError(#[from] serde_json::Error),
 
@@ -58,12 +61,35 @@ pub enum WorkerError {
     #[error("Failed to cast a JsValue")]
     JsCast,
 
-    #[error("Cannot get stub from within a Durable Object")]
-    DurableObjectStub,
+    #[error(transparent)]
+    DurableObject(#[from] crate::durable::DurableObjectError),
+
+    #[error("Persisted field is not bound to storage, mark it with #[persist]")]
+    UnboundPersistedField,
+
+    #[error("counter overflowed adding {0}")]
+    CounterOverflow(i64),
 
     #[error("Invalid message batch. Failed to get id from message.")]
     InvalidMessageBatch,
 
+    #[error("invalid MIME message: {0}")]
+    MimeError(String),
+
+    /// The dispatched Worker does not exist. Told apart from other errors by
+    /// the message of the runtime exception, so this is best-effort.
+    #[error("dispatched worker not found: {0}")]
+    DispatchWorkerNotFound(String),
+
+    /// The dispatched Worker exceeded its CPU or subrequest limits. Told apart
+    /// by undocumented runtime messages, so this is best-effort: an error
+    /// whose wording changes becomes a [WorkerError::AwaitPromise] instead.
+    #[error("dispatched worker exceeded its limits: {0}")]
+    DispatchLimitExceeded(String),
+
+    #[error(transparent)]
+    JwtError(#[from] crate::auth::jwt::JwtError),
+
     #[error(transparent)]
     #[cfg(feature = "d1")]
     D1Error(#[from] betterworker_d1::error::DatabaseError),
@@ -87,6 +113,15 @@ impl WorkerError {
         Self::AwaitPromise(message)
     }
 
+    /// Like [`Self::from_promise_err`], but keeps the flags of the errors the
+    /// runtime throws for calls to a Durable Object.
+    pub(crate) fn from_durable_object_err(err: JsValue) -> Self {
+        match crate::durable::DurableObjectError::from_js(&err) {
+            Some(err) => Self::DurableObject(err),
+            None => Self::from_promise_err(err),
+        }
+    }
+
     pub(crate) fn from_js_err(err: JsValue) -> Self {
         let message = err
             .as_string()
@@ -104,6 +139,11 @@ impl WorkerError {
         Self::JsError(message)
     }
 
+    #[doc(hidden)]
+    pub fn __from_js_err(err: JsValue) -> Self {
+        Self::from_js_err(err)
+    }
+
     pub(crate) fn from_cast_err(_: JsValue) -> Self {
         Self::JsCast
     }
@@ -151,6 +191,11 @@ impl From<serde_wasm_bindgen::Error> for WorkerError {
 
 impl From<WorkerError> for JsValue {
     fn from(e: WorkerError) -> Self {
-        JsValue::from_str(&e.to_string())
+        match e {
+            // Keep the flags, so that a Durable Object can tell its callers to
+            // retry.
+            WorkerError::DurableObject(err) => err.to_js(),
+            e => JsValue::from_str(&e.to_string()),
+        }
     }
 }
@@ -18,6 +18,15 @@ impl Fetcher {
     /// Invoke a fetch event in a worker with a url and optionally a
     /// [RequestInit].
     pub async fn fetch(&self, req: http::Request<Body>) -> Result<http::Response<Body>> {
+        self.fetch_with_map_err(req, WorkerError::from_promise_err)
+            .await
+    }
+
+    /// Like [`Fetcher::fetch`], converting the exception thrown by the
+    /// runtime with `map_err`.
+    pub(crate) async fn fetch_with_map_err(
+        &self, req: http::Request<Body>, map_err: fn(JsValue) -> WorkerError,
+    ) -> Result<http::Response<Body>> {
         let fut = {
             let req = request::into_web_sys_request(req);
             let promise = self.0.fetch(&req);
@@ -25,7 +34,7 @@ impl Fetcher {
             future_from_promise(promise)
         };
 
-        let promise = fut.await.map_err(WorkerError::from_promise_err)?;
+        let promise = fut.await.map_err(map_err)?;
         let res = promise.dyn_into().map_err(WorkerError::from_cast_err)?;
         Ok(response::from_web_sys_response(res))
     }
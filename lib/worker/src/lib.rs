@@ -1,8 +1,11 @@
 #![deny(unsafe_code)]
 
+// Lets `#[durable_object]` expand inside this crate, for `durable::primitives`.
+extern crate self as betterworker;
+
 #[cfg(feature = "d1")]
 pub use betterworker_d1 as d1;
-pub use betterworker_macros::{durable_object, event};
+pub use betterworker_macros::{durable_object, entrypoint, event, workflow};
 #[doc(hidden)]
 pub use betterworker_sys;
 #[doc(hidden)]
@@ -15,14 +18,18 @@ pub use wasm_bindgen_futures;
 pub use worker_kv as kv;
 
 pub mod abort;
+pub mod assets;
+pub mod auth;
 pub mod body;
 pub mod cache;
 pub mod cf;
 pub mod context;
+pub mod crypto;
 pub mod date;
 pub mod delay;
 pub mod durable;
 pub mod dynamic_dispatch;
+pub mod email;
 pub mod env;
 pub mod error;
 pub mod fetch;
@@ -32,11 +39,15 @@ pub mod prelude;
 #[cfg(feature = "queue")]
 pub mod queue;
 pub mod r2;
+pub mod rate_limit;
 pub mod result;
+pub mod rpc;
 pub mod schedule;
 pub mod socket;
 pub mod streams;
+pub mod tail;
 pub mod websocket;
+pub mod workflow;
 
 mod futures;
 
@@ -1,18 +1,25 @@
-pub use betterworker_macros::{durable_object, event};
+pub use betterworker_macros::{durable_object, entrypoint, event, workflow};
 pub use betterworker_sys::{console_debug, console_error, console_log, console_warn};
 pub use http::{Request, Response};
 
 pub use crate::abort::{AbortController, AbortSignal};
+pub use crate::assets::{AssetServer, Assets};
+pub use crate::auth::jwt::{Claims, JwtVerifier};
 pub use crate::body::Body;
 pub use crate::cache::{Cache, CacheDeletionOutcome};
 pub use crate::cf::*;
 pub use crate::context::Context;
+pub use crate::crypto::{
+    CryptoKey, DigestAlgorithm, DigestStream, HashAlgorithm, KeyAlgorithm, KeyData, KeyUsage,
+    SignatureAlgorithm,
+};
 #[cfg(feature = "d1")]
 pub use crate::d1::*;
 pub use crate::date::{Date, DateInit};
 pub use crate::delay::Delay;
 pub use crate::durable::*;
 pub use crate::dynamic_dispatch::*;
+pub use crate::email::{EmailMessage, ForwardableEmailMessage, SendEmail};
 pub use crate::env::{Env, Secret, Var};
 pub use crate::error::WorkerError;
 pub use crate::fetch::fetch;
@@ -20,6 +27,13 @@ pub use crate::fetcher::Fetcher;
 #[cfg(feature = "queue")]
 pub use crate::queue::*;
 pub use crate::r2::*;
+pub use crate::rate_limit::{MissingKey, RateLimit, RateLimitKey, RateLimitOutcome, RateLimiter};
+pub use crate::rpc::{RpcArgs, RpcCall};
 pub use crate::schedule::*;
 pub use crate::streams::*;
+pub use crate::tail::{TraceEvent, TraceItem, TraceOutcome};
 pub use crate::websocket::*;
+pub use crate::workflow::{
+    Backoff, InstanceState, InstanceStatus, StepConfig, Workflow, WorkflowEvent, WorkflowInstance,
+    WorkflowStep,
+};
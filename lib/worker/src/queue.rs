@@ -199,4 +199,25 @@ impl Queue {
         fut.await.map_err(WorkerError::from_promise_err)?;
         Ok(())
     }
+
+    /// Sends multiple messages to the Queue at once. A batch holds at most 100
+    /// messages.
+    pub async fn send_batch<T>(&self, messages: &[T]) -> Result<()>
+    where
+        T: Serialize, {
+        let fut = {
+            let batch = Array::new();
+            for message in messages {
+                let js_value = serde_wasm_bindgen::to_value(message)?;
+                let entry = Object::new();
+                js_sys::Reflect::set(&entry, &JsValue::from_str(BODY_KEY_STR), &js_value)
+                    .map_err(WorkerError::from_js_err)?;
+                batch.push(&entry);
+            }
+            future_from_promise(self.0.send_batch(batch))
+        };
+
+        fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(())
+    }
 }
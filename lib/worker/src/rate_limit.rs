@@ -0,0 +1,256 @@
+//! Bindings for the [Rate Limiting API](https://developers.cloudflare.com/workers/runtime-apis/bindings/rate-limit/).
+//!
+//! A rate limiter is configured in your wrangler.toml file with a `limit` and a
+//! `period` and is accessed with
+//! [`Env::rate_limiter`](crate::env::Env::rate_limiter).
+//!
+//! ```ignore
+//! let limiter = env.rate_limiter("MY_RATE_LIMITER")?;
+//! let limit = RateLimit::new(limiter, RateLimitKey::ClientIp);
+//!
+//! limit.handle(req, |req| async move { handle(req).await }).await
+//! ```
+
+use std::convert::TryFrom;
+use std::future::Future;
+use std::time::Duration;
+
+use betterworker_sys::RateLimiter as RateLimiterSys;
+use js_sys::{JsString, Object as JsObject};
+use send_wrapper::SendWrapper;
+use serde::Deserialize;
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::body::Body;
+use crate::error::WorkerError;
+use crate::futures::future_from_promise;
+use crate::r2::js_object;
+use crate::result::Result;
+
+/// The header Cloudflare sets to the IP address of the connecting client.
+const CF_CONNECTING_IP: &str = "CF-Connecting-IP";
+
+/// An instance of a rate limiting binding.
+pub struct RateLimiter(SendWrapper<RateLimiterSys>);
+
+impl RateLimiter {
+    /// Counts a hit against `key` and reports whether it is still within the
+    /// limit configured for the binding.
+    pub async fn limit(&self, key: impl Into<String>) -> Result<RateLimitOutcome> {
+        let fut = {
+            let options = js_object! {
+                "key" => key.into(),
+            };
+            let promise = self.0.limit(&options).map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        let value = fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+impl AsRef<JsValue> for RateLimiter {
+    fn as_ref(&self) -> &JsValue {
+        &self.0
+    }
+}
+
+impl TryFrom<JsObject> for RateLimiter {
+    type Error = WorkerError;
+
+    fn try_from(obj: JsObject) -> Result<Self> {
+        const TYPE_NAME: &str = "Ratelimit";
+
+        let data = if obj.constructor().name() == TYPE_NAME {
+            obj.unchecked_into()
+        } else {
+            return Err(WorkerError::InvalidBinding);
+        };
+        Ok(Self(SendWrapper::new(data)))
+    }
+}
+
+impl From<RateLimiter> for JsValue {
+    fn from(limiter: RateLimiter) -> Self {
+        JsValue::from(limiter.0.take())
+    }
+}
+
+/// The result of a call to [`RateLimiter::limit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct RateLimitOutcome {
+    /// `false` if the key has exceeded the configured limit.
+    pub success: bool,
+}
+
+/// The part of a request used as the key of a [`RateLimit`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RateLimitKey {
+    /// The client IP address, read from the `CF-Connecting-IP` header.
+    ClientIp,
+    /// The path of the request URI.
+    Path,
+    /// The value of the given request header.
+    Header(http::HeaderName),
+}
+
+impl RateLimitKey {
+    /// Derives the key from a request. Returns `None` if the request does not
+    /// carry the header the key is read from.
+    pub fn extract<B>(&self, req: &http::Request<B>) -> Option<String> {
+        let header = match self {
+            Self::ClientIp => req.headers().get(CF_CONNECTING_IP),
+            Self::Path => return Some(req.uri().path().to_string()),
+            Self::Header(name) => req.headers().get(name),
+        };
+
+        header
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    }
+}
+
+/// What a [`RateLimit`] does with requests from which no key can be derived.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum MissingKey {
+    /// Answers them with `400 Bad Request`, so that clients can't skip the
+    /// limit by leaving out the key.
+    #[default]
+    Reject,
+    /// Lets them through without counting them.
+    Allow,
+    /// Counts them all against the given key.
+    Key(String),
+}
+
+/// Applies a [`RateLimiter`] to incoming requests, answering requests over the
+/// limit with `429 Too Many Requests`.
+///
+/// Requests from which no key can be derived are rejected, unless
+/// [`RateLimit::on_missing_key`] says otherwise.
+pub struct RateLimit {
+    limiter: RateLimiter,
+    key: RateLimitKey,
+    on_missing_key: MissingKey,
+    retry_after: Duration,
+}
+
+impl RateLimit {
+    /// Creates a new rate limit deriving its keys with `key`. The
+    /// `Retry-After` header defaults to 60 seconds.
+    pub fn new(limiter: RateLimiter, key: RateLimitKey) -> Self {
+        Self {
+            limiter,
+            key,
+            on_missing_key: MissingKey::default(),
+            retry_after: Duration::from_secs(60),
+        }
+    }
+
+    /// What to do with requests from which no key can be derived. Defaults to
+    /// [`MissingKey::Reject`].
+    pub fn on_missing_key(mut self, on_missing_key: MissingKey) -> Self {
+        self.on_missing_key = on_missing_key;
+        self
+    }
+
+    /// The value of the `Retry-After` header on limited responses, rounded up
+    /// to whole seconds. This should match the `period` the binding is
+    /// configured with.
+    pub fn retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+
+    /// Counts the request against the limiter. Returns a `429` response if the
+    /// request is over the limit, a `400` response if it has no key and those
+    /// are rejected, or `None` if it may proceed.
+    pub async fn check<B>(&self, req: &http::Request<B>) -> Result<Option<http::Response<Body>>> {
+        let key = match (self.key.extract(req), &self.on_missing_key) {
+            (Some(key), _) => key,
+            (None, MissingKey::Allow) => return Ok(None),
+            (None, MissingKey::Key(key)) => key.clone(),
+            (None, MissingKey::Reject) => {
+                let res = http::Response::builder()
+                    .status(http::StatusCode::BAD_REQUEST)
+                    .body("Missing rate limit key".into())
+                    .unwrap();
+                return Ok(Some(res));
+            },
+        };
+
+        if self.limiter.limit(key).await?.success {
+            return Ok(None);
+        }
+
+        let res = http::Response::builder()
+            .status(http::StatusCode::TOO_MANY_REQUESTS)
+            .header(
+                http::header::RETRY_AFTER,
+                retry_after_secs(self.retry_after),
+            )
+            .body("Too Many Requests".into())
+            .unwrap();
+        Ok(Some(res))
+    }
+
+    /// Runs `handler` if the request is within the limit, and responds with
+    /// `429` otherwise.
+    pub async fn handle<F, Fut>(
+        &self, req: http::Request<Body>, handler: F,
+    ) -> Result<http::Response<Body>>
+    where
+        F: FnOnce(http::Request<Body>) -> Fut,
+        Fut: Future<Output = Result<http::Response<Body>>>, {
+        match self.check(&req).await? {
+            Some(res) => Ok(res),
+            None => handler(req).await,
+        }
+    }
+}
+
+/// `retry_after` in whole seconds, rounded up so that clients never retry
+/// before it has passed.
+fn retry_after_secs(retry_after: Duration) -> u64 {
+    let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    secs.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_keys_from_request() {
+        let req = http::Request::get("https://example.com/a/b?c=d")
+            .header(CF_CONNECTING_IP, "192.0.2.1")
+            .header("x-api-key", "secret")
+            .body(())
+            .unwrap();
+
+        assert_eq!(
+            RateLimitKey::ClientIp.extract(&req).as_deref(),
+            Some("192.0.2.1")
+        );
+        assert_eq!(RateLimitKey::Path.extract(&req).as_deref(), Some("/a/b"));
+        assert_eq!(
+            RateLimitKey::Header(http::HeaderName::from_static("x-api-key"))
+                .extract(&req)
+                .as_deref(),
+            Some("secret")
+        );
+        assert_eq!(
+            RateLimitKey::Header(http::HeaderName::from_static("x-missing")).extract(&req),
+            None
+        );
+    }
+
+    #[test]
+    fn rounds_retry_after_up() {
+        assert_eq!(retry_after_secs(Duration::from_millis(500)), 1);
+        assert_eq!(retry_after_secs(Duration::ZERO), 1);
+        assert_eq!(retry_after_secs(Duration::from_secs(60)), 60);
+        assert_eq!(retry_after_secs(Duration::from_millis(60_001)), 61);
+    }
+}
@@ -0,0 +1,262 @@
+//! Typed [JavaScript RPC](https://developers.cloudflare.com/workers/runtime-apis/rpc/)
+//! between Workers.
+//!
+//! Methods of a `WorkerEntrypoint` behind a service binding are called with
+//! [`Fetcher::call`] or, for calls passing a stream, with [`Fetcher::rpc`].
+//! Arguments and return values are converted with `serde`, so they arrive in
+//! JavaScript as plain objects and arrays.
+//!
+//! ```ignore
+//! let calculator = env.service("CALCULATOR")?;
+//! let sum: i32 = calculator.call("add", (1, 2)).await?;
+//!
+//! let digest: String = calculator
+//!     .rpc("digest")
+//!     .arg("SHA-256")
+//!     .body(req.into_body())
+//!     .call()
+//!     .await?;
+//! ```
+//!
+//! A Rust Worker exposes its own entrypoint with the
+//! [`entrypoint`](crate::entrypoint) attribute. Every `pub async fn` taking
+//! `&self` becomes an RPC method, and arguments and return values of type
+//! [`Body`] are passed as a `ReadableStream`.
+//!
+//! ```ignore
+//! #[entrypoint]
+//! impl Calculator {
+//!     pub fn new(_ctx: Context, _env: Env) -> Self {
+//!         Self
+//!     }
+//!
+//!     pub async fn add(&self, a: i32, b: i32) -> Result<i32> {
+//!         Ok(a + b)
+//!     }
+//! }
+//! ```
+//!
+//! The entrypoint is exported under the name of the type and can be bound by
+//! other Workers with the `entrypoint` field of a service binding.
+//!
+//! Durable Objects expose RPC methods the same way, from an inherent impl block
+//! marked with [`durable_object`](crate::durable_object), and are called
+//! through a [`Stub`] or the typed client generated for them. See
+//! [`crate::durable`].
+
+use js_sys::{Array, Function, Reflect};
+use send_wrapper::SendWrapper;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::body::Body;
+use crate::durable::Stub;
+use crate::dynamic_dispatch::{classify_dispatch_err, DispatchedWorker};
+use crate::error::WorkerError;
+use crate::fetcher::Fetcher;
+use crate::futures::future_from_promise;
+use crate::result::Result;
+
+/// A call to an RPC method, built with [`Fetcher::rpc`], [`Stub::rpc`] or
+/// [`DispatchedWorker::rpc`].
+#[must_use = "RPC calls do nothing unless `call` or `call_body` is awaited"]
+pub struct RpcCall<'a> {
+    target: &'a JsValue,
+    method: String,
+    args: SendWrapper<Array>,
+    error: Option<WorkerError>,
+    /// Converts the error thrown by the method.
+    map_err: fn(JsValue) -> WorkerError,
+}
+
+impl<'a> RpcCall<'a> {
+    pub(crate) fn new(
+        target: &'a JsValue, method: &str, map_err: fn(JsValue) -> WorkerError,
+    ) -> Self {
+        Self {
+            target,
+            method: method.to_string(),
+            args: SendWrapper::new(Array::new()),
+            error: None,
+            map_err,
+        }
+    }
+
+    /// Appends an argument converted with `serde`. Conversion errors are
+    /// returned when the call is made.
+    pub fn arg<T: Serialize + ?Sized>(mut self, value: &T) -> Self {
+        match to_js(value) {
+            Ok(value) => {
+                self.args.push(&value);
+            },
+            Err(err) => {
+                self.error.get_or_insert(err);
+            },
+        }
+        self
+    }
+
+    /// Appends a body, passed to the method as a `ReadableStream`. An empty
+    /// body is passed as `null`.
+    pub fn body(self, body: impl Into<Body>) -> Self {
+        self.args.push(&body_to_js(body.into()));
+        self
+    }
+
+    /// Calls the method and converts its return value with `serde`.
+    pub async fn call<R: DeserializeOwned>(self) -> Result<R> {
+        let value = self.send().await?;
+        from_js(value)
+    }
+
+    /// Calls a method returning a `ReadableStream`.
+    pub async fn call_body(self) -> Result<Body> {
+        let value = self.send().await?;
+        body_from_js(value)
+    }
+
+    async fn send(self) -> Result<JsValue> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+
+        let fut = {
+            let target = self.target;
+            let method = Reflect::get(target, &JsValue::from_str(&self.method))
+                .map_err(WorkerError::from_js_err)?
+                .dyn_into::<Function>()
+                .map_err(|_| {
+                    WorkerError::JsError(format!("RPC method `{}` is not a function", self.method))
+                })?;
+            let value = method
+                .apply(target, &self.args)
+                .map_err(WorkerError::from_js_err)?;
+
+            // RPC methods return an `RpcPromise`, which is a thenable rather than
+            // a `Promise`.
+            future_from_promise(js_sys::Promise::resolve(&value))
+        };
+
+        fut.await.map_err(self.map_err)
+    }
+}
+
+/// The arguments of an RPC call: a tuple of up to eight values, each converted
+/// with `serde` and passed as its own argument. Pass `()` for no arguments and
+/// `(value,)` for a single one, so that a value serialized as an array, such as
+/// a `Vec`, is passed as one argument.
+pub trait RpcArgs {
+    /// Appends the arguments to `call`.
+    fn append_to(self, call: RpcCall<'_>) -> RpcCall<'_>;
+}
+
+macro_rules! rpc_args_impl {
+    ($($name:ident),*) => {
+        impl<$($name: Serialize),*> RpcArgs for ($($name,)*) {
+            #[allow(non_snake_case)]
+            fn append_to(self, call: RpcCall<'_>) -> RpcCall<'_> {
+                let ($($name,)*) = self;
+                call$(.arg(&$name))*
+            }
+        }
+    };
+}
+
+rpc_args_impl!();
+rpc_args_impl!(A);
+rpc_args_impl!(A, B);
+rpc_args_impl!(A, B, C);
+rpc_args_impl!(A, B, C, D);
+rpc_args_impl!(A, B, C, D, E);
+rpc_args_impl!(A, B, C, D, E, F);
+rpc_args_impl!(A, B, C, D, E, F, G);
+rpc_args_impl!(A, B, C, D, E, F, G, H);
+
+impl Fetcher {
+    /// Calls the RPC method `method` of the bound entrypoint.
+    ///
+    /// `args` is a tuple of the arguments, see [`RpcArgs`]. Use
+    /// [`Fetcher::rpc`] to pass a [`Body`].
+    pub async fn call<A, R>(&self, method: &str, args: A) -> Result<R>
+    where
+        A: RpcArgs,
+        R: DeserializeOwned, {
+        args.append_to(self.rpc(method)).call().await
+    }
+
+    /// Starts building a call to the RPC method `method` of the bound
+    /// entrypoint.
+    pub fn rpc(&self, method: &str) -> RpcCall<'_> {
+        RpcCall::new(self.as_ref(), method, WorkerError::from_promise_err)
+    }
+}
+
+impl DispatchedWorker {
+    /// Calls the RPC method `method` of the dispatched Worker, as in
+    /// [`Fetcher::call`].
+    pub async fn call<A, R>(&self, method: &str, args: A) -> Result<R>
+    where
+        A: RpcArgs,
+        R: DeserializeOwned, {
+        args.append_to(self.rpc(method)).call().await
+    }
+
+    /// Starts building a call to the RPC method `method` of the dispatched
+    /// Worker.
+    pub fn rpc(&self, method: &str) -> RpcCall<'_> {
+        RpcCall::new(self.0.as_ref(), method, classify_dispatch_err)
+    }
+}
+
+impl Stub {
+    /// Calls the RPC method `method` of the Durable Object.
+    ///
+    /// `args` is a tuple of the arguments, see [`RpcArgs`].
+    pub async fn call<A, R>(&self, method: &str, args: A) -> Result<R>
+    where
+        A: RpcArgs,
+        R: DeserializeOwned, {
+        args.append_to(self.rpc(method)).call().await
+    }
+
+    /// Starts building a call to the RPC method `method` of the Durable
+    /// Object.
+    pub fn rpc(&self, method: &str) -> RpcCall<'_> {
+        RpcCall::new(self.as_ref(), method, WorkerError::from_durable_object_err)
+    }
+}
+
+/// Converts a value into its RPC representation.
+#[doc(hidden)]
+pub fn to_js<T: Serialize + ?Sized>(value: &T) -> Result<JsValue> {
+    Ok(value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
+
+/// Converts a value from its RPC representation.
+#[doc(hidden)]
+pub fn from_js<T: DeserializeOwned>(value: JsValue) -> Result<T> {
+    Ok(serde_wasm_bindgen::from_value(value)?)
+}
+
+/// Converts a body into a `ReadableStream`, or `null` if it is empty.
+#[doc(hidden)]
+pub fn body_to_js(body: Body) -> JsValue {
+    body.into_stream()
+        .map(JsValue::from)
+        .unwrap_or(JsValue::NULL)
+}
+
+/// Converts a `ReadableStream` into a body. `null` and `undefined` become an
+/// empty body.
+#[doc(hidden)]
+pub fn body_from_js(value: JsValue) -> Result<Body> {
+    if value.is_undefined() || value.is_null() {
+        return Ok(Body::empty());
+    }
+
+    value
+        .dyn_into::<web_sys::ReadableStream>()
+        .map(Body::from)
+        .map_err(WorkerError::from_cast_err)
+}
@@ -0,0 +1,385 @@
+//! Types for [Tail Workers](https://developers.cloudflare.com/workers/observability/logs/tail-workers/),
+//! which receive the events, logs and exceptions of the Workers they are
+//! attached to with the `#[event(tail)]` handler.
+//!
+//! ```ignore
+//! #[event(tail)]
+//! pub async fn tail(items: Vec<TraceItem>, env: Env, _ctx: Context) -> Result<()> {
+//!     let errors = items
+//!         .into_iter()
+//!         .filter(|item| item.outcome != TraceOutcome::Ok || !item.exceptions.is_empty())
+//!         .collect::<Vec<_>>();
+//!
+//!     tail::forward_to_fetcher(&errors, &env.service("LOGS")?, "https://logs/ingest").await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::body::Body;
+use crate::error::WorkerError;
+use crate::fetcher::Fetcher;
+#[cfg(feature = "queue")]
+use crate::queue::Queue;
+use crate::result::Result;
+
+/// The maximum number of messages in a single call to `sendBatch`.
+#[cfg(feature = "queue")]
+const MAX_QUEUE_BATCH: usize = 100;
+
+/// A single invocation of a Worker the Tail Worker is attached to.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceItem {
+    /// The name of the Worker that was invoked.
+    pub script_name: Option<String>,
+    /// The outcome of the invocation.
+    pub outcome: TraceOutcome,
+    /// The time the invocation started.
+    #[serde(default, with = "millis_option")]
+    pub event_timestamp: Option<DateTime<Utc>>,
+    /// The event that triggered the invocation.
+    pub event: Option<TraceEvent>,
+    /// The messages logged with `console`.
+    #[serde(default)]
+    pub logs: Vec<TraceLog>,
+    /// The uncaught exceptions.
+    #[serde(default)]
+    pub exceptions: Vec<TraceException>,
+    /// The events published to `node:diagnostics_channel` channels.
+    #[serde(default)]
+    pub diagnostics_channel_events: Vec<TraceDiagnosticChannelEvent>,
+}
+
+/// The outcome of an invocation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum TraceOutcome {
+    /// The invocation completed.
+    Ok,
+    /// The invocation threw an uncaught exception.
+    Exception,
+    /// The invocation exceeded its CPU time limit.
+    ExceededCpu,
+    /// The invocation exceeded its memory limit.
+    ExceededMemory,
+    /// The Worker could not be found.
+    ScriptNotFound,
+    /// The invocation was canceled, for example because the client
+    /// disconnected.
+    Canceled,
+    /// The client disconnected while the response was streamed.
+    ResponseStreamDisconnected,
+    /// An outcome without a typed representation, holding the outcome as
+    /// reported by the runtime, e.g. `unknown`.
+    Unknown(String),
+}
+
+impl TraceOutcome {
+    /// The outcome as reported by the runtime, e.g. `exceededCpu`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Ok => "ok",
+            Self::Exception => "exception",
+            Self::ExceededCpu => "exceededCpu",
+            Self::ExceededMemory => "exceededMemory",
+            Self::ScriptNotFound => "scriptNotFound",
+            Self::Canceled => "canceled",
+            Self::ResponseStreamDisconnected => "responseStreamDisconnected",
+            Self::Unknown(outcome) => outcome,
+        }
+    }
+}
+
+impl From<String> for TraceOutcome {
+    fn from(outcome: String) -> Self {
+        match outcome.as_str() {
+            "ok" => Self::Ok,
+            "exception" => Self::Exception,
+            "exceededCpu" => Self::ExceededCpu,
+            "exceededMemory" => Self::ExceededMemory,
+            "scriptNotFound" => Self::ScriptNotFound,
+            "canceled" => Self::Canceled,
+            "responseStreamDisconnected" => Self::ResponseStreamDisconnected,
+            _ => Self::Unknown(outcome),
+        }
+    }
+}
+
+impl From<TraceOutcome> for String {
+    fn from(outcome: TraceOutcome) -> Self {
+        match outcome {
+            TraceOutcome::Unknown(outcome) => outcome,
+            outcome => outcome.as_str().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for TraceOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The event that triggered an invocation.
+///
+/// The runtime doesn't tag events with their type, so they are told apart by
+/// their fields.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+#[non_exhaustive]
+pub enum TraceEvent {
+    /// A `fetch` event, which has a `request`.
+    Fetch(FetchEventInfo),
+    /// A `scheduled` event, which has a `cron`.
+    Scheduled(ScheduledEventInfo),
+    /// A `queue` event, which has a `queue` and a `batchSize`.
+    Queue(QueueEventInfo),
+    /// A Durable Object `alarm` event, which only has a `scheduledTime`.
+    Alarm(AlarmEventInfo),
+    /// An event without a typed representation, such as an email or RPC call.
+    Other(serde_json::Value),
+}
+
+/// A `fetch` event.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FetchEventInfo {
+    pub request: FetchEventRequest,
+    /// The response, absent if the Worker did not respond.
+    pub response: Option<FetchEventResponse>,
+}
+
+/// The request of a `fetch` event. Sensitive headers are redacted.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FetchEventRequest {
+    pub url: String,
+    pub method: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// The `cf` properties of the request.
+    pub cf: Option<serde_json::Value>,
+}
+
+/// The response of a `fetch` event.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FetchEventResponse {
+    pub status: u16,
+}
+
+/// A `scheduled` event.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledEventInfo {
+    pub cron: String,
+    /// The scheduled time in milliseconds since the Unix epoch.
+    pub scheduled_time: f64,
+}
+
+/// A `queue` event.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueEventInfo {
+    pub queue: String,
+    pub batch_size: u32,
+}
+
+/// A Durable Object `alarm` event.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlarmEventInfo {
+    /// The scheduled time as an ISO 8601 string.
+    pub scheduled_time: String,
+}
+
+/// A message logged with `console`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TraceLog {
+    /// The time of the log in milliseconds since the Unix epoch.
+    pub timestamp: f64,
+    /// The log level, e.g. `log`, `warn` or `error`.
+    pub level: String,
+    /// The logged values.
+    pub message: serde_json::Value,
+}
+
+/// An uncaught exception.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TraceException {
+    /// The time of the exception in milliseconds since the Unix epoch.
+    pub timestamp: f64,
+    pub name: String,
+    pub message: String,
+    pub stack: Option<String>,
+}
+
+/// An event published to a `node:diagnostics_channel` channel.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TraceDiagnosticChannelEvent {
+    /// The time of the event in milliseconds since the Unix epoch.
+    pub timestamp: f64,
+    pub channel: String,
+    pub message: serde_json::Value,
+}
+
+/// (De)serializes an optional time as milliseconds since the Unix epoch, the
+/// way the runtime reports it.
+mod millis_option {
+    use chrono::{DateTime, TimeZone, Utc};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        time: &Option<DateTime<Utc>>, serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        time.map(|time| time.timestamp_millis())
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        Option::<f64>::deserialize(deserializer)?
+            .map(|millis| {
+                Utc.timestamp_millis_opt(millis as i64)
+                    .single()
+                    .ok_or_else(|| {
+                        serde::de::Error::custom(format!("timestamp {millis} is out of range"))
+                    })
+            })
+            .transpose()
+    }
+}
+
+/// Converts the events passed to the `tail` handler. The events are converted
+/// through JSON, which turns `Date`s into strings and drops values that cannot
+/// be represented.
+#[doc(hidden)]
+pub fn from_js(events: js_sys::Array) -> Result<Vec<TraceItem>> {
+    let json = js_sys::JSON::stringify(&events).map_err(WorkerError::from_js_err)?;
+    Ok(serde_json::from_str(&String::from(json))?)
+}
+
+/// Sends trace items to a Queue, one message per item, in batches.
+#[cfg(feature = "queue")]
+pub async fn forward_to_queue(items: &[TraceItem], queue: &Queue) -> Result<()> {
+    for batch in items.chunks(MAX_QUEUE_BATCH) {
+        queue.send_batch(batch).await?;
+    }
+    Ok(())
+}
+
+/// Sends trace items to a service as a JSON array in the body of a `POST`
+/// request to `url`.
+pub async fn forward_to_fetcher(
+    items: &[TraceItem], fetcher: &Fetcher, url: &str,
+) -> Result<http::Response<Body>> {
+    let req = http::Request::post(url)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_vec(items)?))
+        .map_err(|err| WorkerError::JsError(format!("invalid request: {err}")))?;
+    fetcher.fetch(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn deserializes_trace_items() {
+        let items: Vec<TraceItem> = serde_json::from_str(
+            r#"[
+                {
+                    "scriptName": "api",
+                    "outcome": "exception",
+                    "eventTimestamp": 1700000000000,
+                    "event": {
+                        "request": {
+                            "url": "https://example.com/",
+                            "method": "GET",
+                            "headers": { "accept": "*/*" }
+                        },
+                        "response": { "status": 500 }
+                    },
+                    "logs": [{ "timestamp": 1700000000001, "level": "log", "message": ["hello", 1] }],
+                    "exceptions": [{ "timestamp": 1700000000002, "name": "Error", "message": "boom" }],
+                    "diagnosticsChannelEvents": []
+                },
+                {
+                    "scriptName": "cron",
+                    "outcome": "ok",
+                    "eventTimestamp": null,
+                    "event": { "cron": "* * * * *", "scheduledTime": 1700000000000 },
+                    "logs": [],
+                    "exceptions": [],
+                    "diagnosticsChannelEvents": []
+                },
+                {
+                    "scriptName": null,
+                    "outcome": "ok",
+                    "eventTimestamp": 1700000000000,
+                    "event": { "scheduledTime": "2023-11-14T22:13:20.000Z" },
+                    "logs": [],
+                    "exceptions": [],
+                    "diagnosticsChannelEvents": []
+                },
+                {
+                    "scriptName": "consumer",
+                    "outcome": "ok",
+                    "eventTimestamp": 1700000000000,
+                    "event": { "queue": "jobs", "batchSize": 3 },
+                    "logs": [],
+                    "exceptions": [],
+                    "diagnosticsChannelEvents": []
+                },
+                {
+                    "scriptName": "mail",
+                    "outcome": "ok",
+                    "eventTimestamp": 1700000000000,
+                    "event": { "mailFrom": "a@example.com", "rcptTo": "b@example.com", "rawSize": 10 },
+                    "logs": [],
+                    "exceptions": [],
+                    "diagnosticsChannelEvents": []
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            &items[0].event,
+            Some(TraceEvent::Fetch(FetchEventInfo {
+                response: Some(FetchEventResponse { status: 500 }),
+                ..
+            }))
+        ));
+        assert_eq!(items[0].outcome, TraceOutcome::Exception);
+        assert_eq!(
+            items[0].event_timestamp,
+            Utc.timestamp_millis_opt(1700000000000).single()
+        );
+        assert_eq!(items[1].event_timestamp, None);
+        assert_eq!(items[0].logs[0].message, serde_json::json!(["hello", 1]));
+        assert_eq!(items[0].exceptions[0].stack, None);
+        assert!(
+            matches!(&items[1].event, Some(TraceEvent::Scheduled(info)) if info.cron == "* * * * *")
+        );
+        assert!(matches!(&items[2].event, Some(TraceEvent::Alarm(_))));
+        assert!(matches!(&items[3].event, Some(TraceEvent::Queue(info)) if info.batch_size == 3));
+        assert!(matches!(&items[4].event, Some(TraceEvent::Other(_))));
+    }
+
+    #[test]
+    fn keeps_unknown_outcomes() {
+        let outcome: TraceOutcome = serde_json::from_str(r#""exceededCpu""#).unwrap();
+        assert_eq!(outcome, TraceOutcome::ExceededCpu);
+
+        let outcome: TraceOutcome = serde_json::from_str(r#""daemonDown""#).unwrap();
+        assert_eq!(outcome, TraceOutcome::Unknown("daemonDown".into()));
+        assert_eq!(serde_json::to_string(&outcome).unwrap(), r#""daemonDown""#);
+    }
+}
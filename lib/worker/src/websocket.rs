@@ -7,11 +7,12 @@ use betterworker_sys::WebSocketPair as WebSocketPairSys;
 use futures_channel::mpsc::UnboundedReceiver;
 use futures_util::Stream;
 use send_wrapper::SendWrapper;
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 use url::Url;
 use wasm_bindgen::convert::FromWasmAbi;
 use wasm_bindgen::prelude::Closure;
-use wasm_bindgen::JsCast;
+use wasm_bindgen::{JsCast, JsValue};
 
 use crate::error::WorkerError;
 use crate::fetch::fetch;
@@ -144,6 +145,29 @@ impl WebSocket {
         .map_err(WorkerError::from_js_err)
     }
 
+    /// Keeps a copy of `value` associated with the `WebSocket`, which survives
+    /// the hibernation of a Durable Object. The serialized value is limited to
+    /// 2,048 bytes.
+    pub fn serialize_attachment<T: Serialize>(&self, value: &T) -> Result<()> {
+        self.socket
+            .serialize_attachment(serde_wasm_bindgen::to_value(value)?)
+            .map_err(WorkerError::from_js_err)
+    }
+
+    /// Retrieves the value stored with [`Self::serialize_attachment`], or
+    /// `None` if there is none.
+    pub fn deserialize_attachment<T: DeserializeOwned>(&self) -> Result<Option<T>> {
+        let value = self
+            .socket
+            .deserialize_attachment()
+            .map_err(WorkerError::from_js_err)?;
+        if value.is_null() || value.is_undefined() {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_wasm_bindgen::from_value(value)?))
+    }
+
     /// Internal utility method to avoid verbose code.
     /// This method registers a closure in the underlying JS environment, which
     /// calls back into the Rust/wasm environment.
@@ -304,6 +328,23 @@ impl PinnedDrop for EventStream<'_> {
     }
 }
 
+/// A message received by a hibernatable `WebSocket` of a Durable Object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebSocketIncomingMessage {
+    String(String),
+    Binary(Vec<u8>),
+}
+
+impl WebSocketIncomingMessage {
+    #[doc(hidden)]
+    pub fn from_js(message: JsValue) -> Self {
+        match message.as_string() {
+            Some(text) => Self::String(text),
+            None => Self::Binary(js_sys::Uint8Array::new(&message).to_vec()),
+        }
+    }
+}
+
 impl From<web_sys::WebSocket> for WebSocket {
     fn from(socket: web_sys::WebSocket) -> Self {
         Self {
@@ -0,0 +1,516 @@
+//! [Workflows](https://developers.cloudflare.com/workflows/) are durable,
+//! multi-step programs whose steps are retried and whose results are persisted.
+//!
+//! A workflow is written as an impl block with the
+//! [`workflow`](macro@crate::workflow) attribute, which is exported under the
+//! name of the type as a `WorkflowEntrypoint` for the `class_name` of the
+//! `[[workflows]]` table in your wrangler.toml file.
+//!
+//! ```ignore
+//! #[workflow]
+//! impl Onboarding {
+//!     pub fn new(_ctx: Context, env: Env) -> Self {
+//!         Self { env }
+//!     }
+//!
+//!     pub async fn run(&self, event: WorkflowEvent<User>, step: WorkflowStep) -> Result<()> {
+//!         let user = event.payload;
+//!         let config = StepConfig::new().retries(5, Duration::from_secs(10), Backoff::Exponential);
+//!         let account: Account = step
+//!             .do_with_config("create account", config, move || create_account(user.clone()))
+//!             .await?;
+//!
+//!         step.sleep("wait a day", Duration::from_secs(24 * 60 * 60)).await?;
+//!         step.do_("send reminder", move || send_reminder(account.clone())).await
+//!     }
+//! }
+//! ```
+//!
+//! Instances are managed through the [`Workflow`] binding.
+//!
+//! ```ignore
+//! let instance = env.workflow("ONBOARDING")?.create(&user).await?;
+//! let status = instance.status().await?;
+//! ```
+
+use std::convert::TryFrom;
+use std::future::Future;
+use std::time::Duration;
+
+use betterworker_sys::{
+    Workflow as WorkflowSys, WorkflowInstance as WorkflowInstanceSys,
+    WorkflowStep as WorkflowStepSys,
+};
+use js_sys::{JsString, Object as JsObject};
+use send_wrapper::SendWrapper;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::future_to_promise;
+
+use crate::date::Date;
+use crate::error::WorkerError;
+use crate::futures::future_from_promise;
+use crate::r2::js_object;
+use crate::result::Result;
+use crate::rpc;
+
+/// A binding to a workflow, used to create and manage its instances.
+pub struct Workflow(SendWrapper<WorkflowSys>);
+
+impl Workflow {
+    /// Starts a new instance with a random id, passing `params` as the payload
+    /// of its event.
+    pub async fn create<T: Serialize>(&self, params: &T) -> Result<WorkflowInstance> {
+        let fut = {
+            let options = js_object! {
+                "params" => rpc::to_js(params)?,
+            };
+            let promise = self.0.create(&options).map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        let instance = fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(WorkflowInstance(SendWrapper::new(
+            instance.unchecked_into(),
+        )))
+    }
+
+    /// Starts a new instance with the given id, passing `params` as the
+    /// payload of its event.
+    pub async fn create_with_id<T: Serialize>(
+        &self, id: &str, params: &T,
+    ) -> Result<WorkflowInstance> {
+        let fut = {
+            let options = js_object! {
+                "id" => id,
+                "params" => rpc::to_js(params)?,
+            };
+            let promise = self.0.create(&options).map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        let instance = fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(WorkflowInstance(SendWrapper::new(
+            instance.unchecked_into(),
+        )))
+    }
+
+    /// Gets an existing instance by its id.
+    pub async fn get(&self, id: &str) -> Result<WorkflowInstance> {
+        let fut = {
+            let promise = self.0.get(id).map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        let instance = fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(WorkflowInstance(SendWrapper::new(
+            instance.unchecked_into(),
+        )))
+    }
+}
+
+impl AsRef<JsValue> for Workflow {
+    fn as_ref(&self) -> &JsValue {
+        &self.0
+    }
+}
+
+impl TryFrom<JsObject> for Workflow {
+    type Error = WorkerError;
+
+    fn try_from(obj: JsObject) -> Result<Self> {
+        // Workflow bindings are not instances of a class with a stable name, so
+        // check for the methods instead.
+        let is_workflow = ["create", "get"].iter().all(|method| {
+            js_sys::Reflect::get(&obj, &JsValue::from_str(method))
+                .map(|value| value.is_function())
+                .unwrap_or(false)
+        });
+        if !is_workflow {
+            return Err(WorkerError::InvalidBinding);
+        }
+        Ok(Self(SendWrapper::new(obj.unchecked_into())))
+    }
+}
+
+impl From<Workflow> for JsValue {
+    fn from(workflow: Workflow) -> Self {
+        JsValue::from(workflow.0.take())
+    }
+}
+
+/// An instance of a workflow.
+pub struct WorkflowInstance(SendWrapper<WorkflowInstanceSys>);
+
+impl WorkflowInstance {
+    /// The id of the instance.
+    pub fn id(&self) -> String {
+        self.0.id()
+    }
+
+    /// Pauses the instance. It stops at its next step.
+    pub async fn pause(&self) -> Result<()> {
+        let fut = {
+            let promise = self.0.pause().map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(())
+    }
+
+    /// Resumes a paused instance.
+    pub async fn resume(&self) -> Result<()> {
+        let fut = {
+            let promise = self.0.resume().map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(())
+    }
+
+    /// Terminates the instance.
+    pub async fn terminate(&self) -> Result<()> {
+        let fut = {
+            let promise = self.0.terminate().map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(())
+    }
+
+    /// Restarts the instance from its first step.
+    pub async fn restart(&self) -> Result<()> {
+        let fut = {
+            let promise = self.0.restart().map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(())
+    }
+
+    /// The current status of the instance.
+    pub async fn status(&self) -> Result<InstanceStatus> {
+        let fut = {
+            let promise = self.0.status().map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        let status = fut.await.map_err(WorkerError::from_promise_err)?;
+        rpc::from_js(status)
+    }
+
+    /// Sends an event to the instance, resuming a
+    /// [`WorkflowStep::wait_for_event`] waiting for `event_type`.
+    pub async fn send_event<T: Serialize>(&self, event_type: &str, payload: &T) -> Result<()> {
+        let fut = {
+            let event = js_object! {
+                "type" => event_type,
+                "payload" => rpc::to_js(payload)?,
+            };
+            let promise = self
+                .0
+                .send_event(&event)
+                .map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(())
+    }
+}
+
+/// The status of a [`WorkflowInstance`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct InstanceStatus {
+    pub status: InstanceState,
+    /// The error of an errored instance.
+    pub error: Option<serde_json::Value>,
+    /// The value returned by the `run` method of a complete instance.
+    pub output: Option<serde_json::Value>,
+}
+
+/// The state of a [`WorkflowInstance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InstanceState {
+    Queued,
+    Running,
+    Paused,
+    Errored,
+    Terminated,
+    Complete,
+    Waiting,
+    WaitingForPause,
+    #[serde(other)]
+    Unknown,
+}
+
+/// The event that started a workflow instance, passed to its `run` method.
+#[derive(Debug)]
+pub struct WorkflowEvent<T> {
+    /// The parameters the instance was created with.
+    pub payload: T,
+    /// The time the instance was created.
+    pub timestamp: Date,
+    /// The id of the instance.
+    pub instance_id: String,
+}
+
+impl<T: DeserializeOwned> WorkflowEvent<T> {
+    #[doc(hidden)]
+    pub fn from_js(event: JsValue) -> Result<Self> {
+        let get = |key: &str| {
+            js_sys::Reflect::get(&event, &JsValue::from_str(key)).map_err(WorkerError::from_js_err)
+        };
+
+        Ok(Self {
+            payload: rpc::from_js(get("payload")?)?,
+            timestamp: Date::from(js_sys::Date::from(get("timestamp")?)),
+            instance_id: get("instanceId")?.as_string().unwrap_or_default(),
+        })
+    }
+}
+
+/// An event received with [`WorkflowStep::wait_for_event`].
+#[derive(Debug)]
+pub struct WorkflowStepEvent<T> {
+    pub payload: T,
+    /// The time the event was sent.
+    pub timestamp: Date,
+    pub event_type: String,
+}
+
+/// How the delay between retries of a step grows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Backoff {
+    Constant,
+    Linear,
+    Exponential,
+}
+
+/// The retry policy of a step.
+#[derive(Debug, Clone, Serialize)]
+pub struct RetryConfig {
+    /// The maximum number of retries.
+    pub limit: u32,
+    /// The delay before the first retry.
+    #[serde(serialize_with = "serialize_millis")]
+    pub delay: Duration,
+    pub backoff: Backoff,
+}
+
+/// The configuration of a step run with [`WorkflowStep::do_with_config`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StepConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<RetryConfig>,
+    /// The maximum duration of a single attempt.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_millis"
+    )]
+    pub timeout: Option<Duration>,
+}
+
+impl StepConfig {
+    /// Creates a configuration with the default retry policy and timeout.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retries the step up to `limit` times, waiting `delay` before the first
+    /// retry.
+    pub fn retries(mut self, limit: u32, delay: Duration, backoff: Backoff) -> Self {
+        self.retries = Some(RetryConfig {
+            limit,
+            delay,
+            backoff,
+        });
+        self
+    }
+
+    /// Fails an attempt of the step that takes longer than `timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+fn serialize_millis<S: Serializer>(
+    duration: &Duration, serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_millis() as u64)
+}
+
+fn serialize_optional_millis<S: Serializer>(
+    duration: &Option<Duration>, serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serialize_millis(duration, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// The steps of a workflow instance, passed to its `run` method.
+///
+/// The result of every step is persisted under its name, so a step that
+/// completed is not run again when the instance is resumed. Step names must
+/// therefore be unique and deterministic.
+pub struct WorkflowStep(SendWrapper<WorkflowStepSys>);
+
+impl WorkflowStep {
+    /// Runs a step with the default retry policy and returns its result.
+    ///
+    /// `f` is called again for every retry, and its result must be
+    /// serializable so it can be persisted.
+    pub async fn do_<T, F, Fut>(&self, name: &str, f: F) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = Result<T>> + 'static, {
+        self.run_step(name, None, f).await
+    }
+
+    /// Runs a step with the given retry policy and timeout and returns its
+    /// result.
+    pub async fn do_with_config<T, F, Fut>(
+        &self, name: &str, config: StepConfig, f: F,
+    ) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = Result<T>> + 'static, {
+        self.run_step(name, Some(config), f).await
+    }
+
+    async fn run_step<T, F, Fut>(&self, name: &str, config: Option<StepConfig>, f: F) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = Result<T>> + 'static, {
+        let (fut, callback) = {
+            let callback = Closure::<dyn Fn() -> js_sys::Promise>::new(move || {
+                let fut = f();
+                future_to_promise(async move {
+                    let value = fut
+                        .await
+                        .map_err(|err| JsValue::from(js_sys::Error::new(&err.to_string())))?;
+                    rpc::to_js(&value).map_err(JsValue::from)
+                })
+            });
+            let function = callback.as_ref().unchecked_ref();
+            let promise = match config {
+                Some(config) => self.0.do_with_config(name, &rpc::to_js(&config)?, function),
+                None => self.0.do_(name, function),
+            }
+            .map_err(WorkerError::from_js_err)?;
+            (future_from_promise(promise), SendWrapper::new(callback))
+        };
+
+        let value = fut.await.map_err(WorkerError::from_promise_err)?;
+        drop(callback);
+        rpc::from_js(value)
+    }
+
+    /// Sleeps for `duration`.
+    pub async fn sleep(&self, name: &str, duration: Duration) -> Result<()> {
+        let fut = {
+            let promise = self
+                .0
+                .sleep(name, duration.as_millis() as f64)
+                .map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(())
+    }
+
+    /// Sleeps until `timestamp`.
+    pub async fn sleep_until(&self, name: &str, timestamp: impl Into<Date>) -> Result<()> {
+        let fut = {
+            let timestamp: js_sys::Date = timestamp.into().into();
+            let promise = self
+                .0
+                .sleep_until(name, &timestamp)
+                .map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        fut.await.map_err(WorkerError::from_promise_err)?;
+        Ok(())
+    }
+
+    /// Waits for an event of `event_type` sent with
+    /// [`WorkflowInstance::send_event`]. Fails if no event arrives within
+    /// `timeout`, which defaults to 24 hours.
+    pub async fn wait_for_event<T: DeserializeOwned>(
+        &self, name: &str, event_type: &str, timeout: Option<Duration>,
+    ) -> Result<WorkflowStepEvent<T>> {
+        let fut = {
+            let options = js_object! {
+                "type" => event_type,
+            };
+            if let Some(timeout) = timeout {
+                js_sys::Reflect::set(
+                    &options,
+                    &JsValue::from_str("timeout"),
+                    &JsValue::from_f64(timeout.as_millis() as f64),
+                )
+                .map_err(WorkerError::from_js_err)?;
+            }
+            let promise = self
+                .0
+                .wait_for_event(name, &options)
+                .map_err(WorkerError::from_js_err)?;
+            future_from_promise(promise)
+        };
+
+        let event = fut.await.map_err(WorkerError::from_promise_err)?;
+        let get = |key: &str| {
+            js_sys::Reflect::get(&event, &JsValue::from_str(key)).map_err(WorkerError::from_js_err)
+        };
+        Ok(WorkflowStepEvent {
+            payload: rpc::from_js(get("payload")?)?,
+            timestamp: Date::from(js_sys::Date::from(get("timestamp")?)),
+            event_type: get("type")?.as_string().unwrap_or_default(),
+        })
+    }
+}
+
+impl From<WorkflowStepSys> for WorkflowStep {
+    fn from(inner: WorkflowStepSys) -> Self {
+        Self(SendWrapper::new(inner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_step_config() {
+        let config = StepConfig::new()
+            .retries(5, Duration::from_secs(10), Backoff::Exponential)
+            .timeout(Duration::from_secs(60));
+
+        assert_eq!(
+            serde_json::to_value(config).unwrap(),
+            serde_json::json!({
+                "retries": { "limit": 5, "delay": 10000, "backoff": "exponential" },
+                "timeout": 60000,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(StepConfig::new()).unwrap(),
+            serde_json::json!({})
+        );
+    }
+}